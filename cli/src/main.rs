@@ -0,0 +1,69 @@
+//! Scores a game of Tycoon from a transcript of who went out when, using the same rules as the
+//! web app. See `transcript` for the format. With `--tui` it scores a game as it's played instead.
+
+use std::env;
+use std::fs;
+use std::io::{self, Read};
+use std::process;
+
+mod replay;
+mod transcript;
+mod tui;
+
+const USAGE: &str = "usage: tycoon-scorer [TRANSCRIPT]
+       tycoon-scorer --tui
+
+Reads a transcript from the file given, or from standard input, and prints the
+ranks, points and card exchanges for each round followed by the final standings.
+
+A transcript looks like this (rounds: and scoring: are optional):
+
+    players: Alice, Bob, Carol, Dave
+    rounds: 3
+    scoring: 30/20/15/10/0
+    Carol, Alice, Bob
+    Alice, Dave, Bob
+
+With --tui, scores a game interactively in the terminal instead.
+";
+
+fn read_input(path: Option<&str>) -> io::Result<String> {
+    match path {
+        Some("-") | None => {
+            let mut text = String::new();
+            io::stdin().read_to_string(&mut text)?;
+            Ok(text)
+        }
+        Some(path) => fs::read_to_string(path),
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.len() > 1 || args.iter().any(|a| a == "-h" || a == "--help") {
+        eprint!("{}", USAGE);
+        process::exit(2);
+    }
+    if args.first().map(String::as_str) == Some("--tui") {
+        if let Err(e) = tui::run() {
+            eprintln!("tycoon-scorer: {}", e);
+            process::exit(1);
+        }
+        return;
+    }
+
+    let text = match read_input(args.first().map(String::as_str)) {
+        Ok(text) => text,
+        Err(e) => {
+            eprintln!("tycoon-scorer: could not read the transcript: {}", e);
+            process::exit(1);
+        }
+    };
+    match transcript::parse(&text).and_then(|t| replay::replay(&t)) {
+        Ok(report) => print!("{}", report),
+        Err(e) => {
+            eprintln!("tycoon-scorer: {}", e);
+            process::exit(1);
+        }
+    }
+}
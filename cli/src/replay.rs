@@ -0,0 +1,175 @@
+use std::cmp::Reverse;
+use std::fmt::Write;
+use tycoon_core::{exchange, Game, Rank};
+
+use crate::transcript::{Transcript, TranscriptError};
+
+fn pid_of(game: &Game, name: &str) -> Option<usize> {
+    game.players.iter().position(|p| p.name == name)
+}
+
+/// Plays a transcript through the same rules as the web app and writes up what happened: the
+/// ranks and points for each round, the card exchange before the next one, and the final
+/// standings.
+pub fn replay(transcript: &Transcript) -> Result<String, TranscriptError> {
+    let mut game = Game::new(transcript.game_length, transcript.scoring);
+    game.tie_break = transcript.tie_break;
+    game.bankruptcy = transcript.bankruptcy;
+    game.set_players(&transcript.players);
+    let mut report = String::new();
+
+    for round in transcript.rounds.iter() {
+        if game.is_game_over() {
+            return Err(TranscriptError::new(
+                round.line,
+                "the game was already over before this round",
+            ));
+        }
+        for name in round.order.iter() {
+            // A bankrupt tycoon is already out, so listing them is harmless
+            if let Some(pid) = pid_of(&game, name) {
+                game.handle_go_out(pid);
+            }
+        }
+        if !game.is_round_complete() {
+            return Err(TranscriptError::new(
+                round.line,
+                "everyone but the last player has to go out",
+            ));
+        }
+
+        writeln!(report, "Round {}", game.round).unwrap();
+        let mut results: Vec<(usize, Rank)> =
+            game.players_out.iter().map(|(pid, r)| (*pid, *r)).collect();
+        results.sort_by_key(|(pid, rank)| (Reverse(*rank), *pid));
+        if let Some(message) = &game.beggar_message {
+            writeln!(report, "  {}", message).unwrap();
+        }
+        game.handle_end_round();
+        for (pid, rank) in results {
+            let player = &game.players[pid];
+            writeln!(
+                report,
+                "  {:<8} {:<16} {:>+5}  total {}",
+                rank.to_string(),
+                player.name,
+                player.round_points.last().copied().unwrap_or(0),
+                player.score
+            )
+            .unwrap();
+        }
+
+        if !game.is_game_over() {
+            writeln!(report, "Card exchange before round {}", game.round).unwrap();
+            for instruction in exchange::instructions(&game.players) {
+                writeln!(report, "  {}", instruction.describe(&game.players)).unwrap();
+            }
+            // Transcripts don't record the exchange, so take it as done
+            game.confirm_all_exchanges();
+            game.start_round();
+        }
+        writeln!(report).unwrap();
+    }
+
+    if game.is_game_over() {
+        writeln!(report, "Final standings").unwrap();
+    } else {
+        writeln!(
+            report,
+            "Standings after {} (game not finished)",
+            game.game_length.round_label(game.round - 1)
+        )
+        .unwrap();
+    }
+    for standing in game.standings() {
+        let player = &game.players[standing.pid];
+        writeln!(
+            report,
+            "  {:<10} {:<16} {}",
+            standing.place_text(),
+            player.name,
+            player.score
+        )
+        .unwrap();
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcript::parse;
+
+    #[test]
+    fn replays_bankruptcy_and_exchange() {
+        let transcript = parse(
+            "players: Alice, Bob, Carol, Dave\n\
+             Alice, Bob, Carol\n\
+             Carol, Dave\n",
+        )
+        .unwrap();
+        let report = replay(&transcript).unwrap();
+        assert_eq!(
+            report,
+            "Round 1\n\
+             \x20 Tycoon   Alice              +30  total 30\n\
+             \x20 Rich     Bob                +20  total 20\n\
+             \x20 Poor     Carol              +10  total 10\n\
+             \x20 Beggar   Dave                +0  total 0\n\
+             Card exchange before round 2\n\
+             \x20 Alice: passes two cards of their choice to Dave, and receives no extra cards.\n\
+             \x20 Bob: passes one card of their choice to Carol, and receives the third extra card (if applicable)\n\
+             \x20 Carol: passes their highest card to Bob, and receives the second extra card (if applicable)\n\
+             \x20 Dave: passes their two highest cards to Alice, and receives the first extra card (if applicable)\n\
+             \n\
+             Round 2\n\
+             \x20 Carol went out so Alice is the beggar automatically.\n\
+             \x20 Tycoon   Carol              +30  total 40\n\
+             \x20 Rich     Dave               +20  total 20\n\
+             \x20 Poor     Bob                +10  total 30\n\
+             \x20 Beggar   Alice               +0  total 30\n\
+             \n\
+             Final standings\n\
+             \x20 1st        Carol            40\n\
+             \x20 2nd (tie)  Alice            30\n\
+             \x20 2nd (tie)  Bob              30\n\
+             \x20 4th        Dave             20\n"
+        );
+    }
+
+    #[test]
+    fn unfinished_rounds_are_an_error() {
+        let transcript = parse("players: A, B, C, D\nA, B\n").unwrap();
+        assert_eq!(
+            replay(&transcript).unwrap_err(),
+            TranscriptError::new(2, "everyone but the last player has to go out")
+        );
+    }
+
+    #[test]
+    fn rounds_after_the_end_are_an_error() {
+        let transcript = parse("players: A, B, C\nrounds: 1\nA, B\nB, C\n").unwrap();
+        assert_eq!(replay(&transcript).unwrap_err().line, 4);
+    }
+
+    #[test]
+    fn ties_are_broken_as_the_transcript_says() {
+        let transcript = parse(
+            "players: Alice, Bob, Carol, Dave\n\
+             tie-break: tycoons\n\
+             Alice, Bob, Carol\n\
+             Carol, Dave\n",
+        )
+        .unwrap();
+        let report = replay(&transcript).unwrap();
+        assert!(report.contains("  2nd        Alice"));
+        assert!(report.contains("  3rd        Bob"));
+    }
+
+    #[test]
+    fn short_transcripts_report_standings_so_far() {
+        let transcript = parse("players: A, B, C\nrounds: 5\nA, B\n").unwrap();
+        let report = replay(&transcript).unwrap();
+        assert!(report.contains("Standings after Round 1 of 5 (game not finished)"));
+    }
+}
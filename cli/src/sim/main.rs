@@ -0,0 +1,211 @@
+//! Plays thousands of games between bots under one set of rules and reports how the ranks hold
+//! up, for settling arguments about which house rules make the Tycoon too strong.
+
+use std::env;
+use std::process;
+use tycoon_core::{Bankruptcy, Difficulty, GameLength, ScoringTable, MAX_PLAYERS, MIN_PLAYERS};
+
+mod simulation;
+
+use crate::simulation::Settings;
+
+const USAGE: &str = "usage: tycoon-sim [OPTIONS]
+
+Plays games between bots and reports how often each rank is kept from one round
+to the next, the average points earned starting from each rank, and how often
+the Tycoon goes bankrupt.
+
+Options:
+    --games N             games to play (default 1000)
+    --rounds N            rounds in each game (default 5)
+    --points N            play each game to a target score instead
+    --scoring S           a preset name or points like 30/20/15/10/0
+    --bankruptcy RULE     off, beggar (the default) or drop-out
+    --no-exchange         skip the card exchange between rounds
+    --revolution-bonus N  points for each revolution
+    --foul-finish         send players who go out on a foul card to the bottom
+    --decks N             decks in play (default 1)
+    --jokers N            jokers in play (default 1)
+    --bots LIST           each seat's bot, e.g. greedy,greedy,random,lookahead
+                          (default four greedy bots)
+    --seed N              seed for the shuffle (default 1)
+";
+
+fn number<T: std::str::FromStr>(option: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("{} needs a number, not {:?}", option, value))
+}
+
+fn parse_bots(value: &str) -> Result<Vec<Difficulty>, String> {
+    let seats = value
+        .split(',')
+        .map(|key| {
+            Difficulty::from_key(key.trim()).ok_or_else(|| {
+                format!(
+                    "{:?} isn't a bot; choose random, greedy or lookahead",
+                    key.trim()
+                )
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if !(MIN_PLAYERS..=MAX_PLAYERS).contains(&seats.len()) {
+        return Err(format!(
+            "Tycoon needs between {} and {} players, not {}",
+            MIN_PLAYERS,
+            MAX_PLAYERS,
+            seats.len()
+        ));
+    }
+    Ok(seats)
+}
+
+fn parse_args(args: &[String]) -> Result<Settings, String> {
+    let mut settings = Settings::default();
+    let mut args = args.iter();
+    while let Some(option) = args.next() {
+        let option = option.as_str();
+        match option {
+            "--no-exchange" => {
+                settings.exchange = false;
+                continue;
+            }
+            "--foul-finish" => {
+                settings.foul_finish = true;
+                continue;
+            }
+            _ => {}
+        }
+        let value = args
+            .next()
+            .ok_or_else(|| format!("{} needs a value", option))?
+            .as_str();
+        match option {
+            "--games" => settings.games = number(option, value)?,
+            "--rounds" => settings.game_length = GameLength::Rounds(number(option, value)?),
+            "--points" => settings.game_length = GameLength::Points(number(option, value)?),
+            "--scoring" => {
+                settings.scoring = ScoringTable::parse(value).ok_or_else(|| {
+                    format!(
+                        "scoring should be a preset or five numbers like 30/20/15/10/0, not {:?}",
+                        value
+                    )
+                })?
+            }
+            "--bankruptcy" => {
+                settings.bankruptcy = Bankruptcy::from_key(value).ok_or_else(|| {
+                    format!(
+                        "bankruptcy should be off, beggar or drop-out, not {:?}",
+                        value
+                    )
+                })?
+            }
+            "--revolution-bonus" => settings.revolution_bonus = number(option, value)?,
+            "--decks" => {
+                let deck = settings.deck.with_decks(value);
+                if deck.decks.to_string() != value {
+                    return Err(format!("--decks should be 1 to 3, not {:?}", value));
+                }
+                settings.deck = deck;
+            }
+            "--jokers" => {
+                let deck = settings.deck.with_jokers(value);
+                if deck.jokers.to_string() != value {
+                    return Err(format!(
+                        "--jokers should be at most {}, not {:?}",
+                        settings.deck.max_jokers(),
+                        value
+                    ));
+                }
+                settings.deck = deck;
+            }
+            "--bots" => settings.seats = parse_bots(value)?,
+            "--seed" => settings.seed = number(option, value)?,
+            _ => return Err(format!("unknown option {}", option)),
+        }
+    }
+    match settings.game_length {
+        GameLength::Rounds(0) => return Err("games need at least one round".into()),
+        GameLength::Points(0) => return Err("the target score has to be above 0".into()),
+        _ => {}
+    }
+    if !settings.target_reachable() {
+        return Err(
+            "nobody can score points with this scoring, so the target can't be reached".into(),
+        );
+    }
+    Ok(settings)
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.iter().any(|a| a == "-h" || a == "--help") {
+        print!("{}", USAGE);
+        return;
+    }
+    let settings = match parse_args(&args) {
+        Ok(settings) => settings,
+        Err(e) => {
+            eprintln!("tycoon-sim: {}\n\n{}", e, USAGE);
+            process::exit(2);
+        }
+    };
+    match simulation::simulate(&settings) {
+        Ok(stats) => print!("{}", stats),
+        Err(e) => {
+            eprintln!("tycoon-sim: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(text: &str) -> Vec<String> {
+        text.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn parses_options() {
+        let settings = parse_args(&args(
+            "--games 10 --points 100 --bankruptcy off --no-exchange --bots random,greedy,lookahead",
+        ))
+        .unwrap();
+        assert_eq!(settings.games, 10);
+        assert_eq!(settings.game_length, GameLength::Points(100));
+        assert_eq!(settings.bankruptcy, Bankruptcy::Off);
+        assert!(!settings.exchange);
+        assert_eq!(settings.seats.len(), 3);
+        assert_eq!(
+            parse_args(&args("--scoring 3/2/1/1/0"))
+                .unwrap()
+                .scoring
+                .tycoon,
+            3
+        );
+    }
+
+    #[test]
+    fn rejects_bad_options() {
+        assert!(parse_args(&args("--games lots")).is_err());
+        assert!(parse_args(&args("--bots greedy,greedy")).is_err());
+        assert!(parse_args(&args("--bots greedy,smart,greedy")).is_err());
+        assert!(parse_args(&args("--decks 4")).is_err());
+        assert!(parse_args(&args("--rounds")).is_err());
+        assert!(parse_args(&args("--shuffle")).is_err());
+        assert!(parse_args(&["--scoring".to_string(), " ".to_string()]).is_err());
+    }
+
+    #[test]
+    fn rejects_targets_nobody_can_reach() {
+        assert!(parse_args(&args("--games 1 --points 10 --scoring 0/0/0/0/0")).is_err());
+        assert!(parse_args(&args("--points 0")).is_err());
+        // Reachable, but plus/minus scoring takes well over 255 rounds to get there
+        let settings = parse_args(&args("--games 1 --points 1000 --scoring plus")).unwrap();
+        let stats = simulation::simulate(&settings).unwrap();
+        assert_eq!(stats.games, 1);
+        assert!(stats.rounds > 255);
+    }
+}
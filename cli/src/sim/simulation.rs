@@ -0,0 +1,332 @@
+use std::fmt;
+use tycoon_core::{
+    bots, Bankruptcy, Deck, Difficulty, Game, GameLength, Rank, ScoringTable, Shuffler, Table,
+};
+
+/// The rules and players for a batch of simulated games.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+    pub games: usize,
+    pub game_length: GameLength,
+    pub scoring: ScoringTable,
+    pub bankruptcy: Bankruptcy,
+    pub revolution_bonus: i32,
+    pub foul_finish: bool,
+    pub deck: Deck,
+    /// Whether cards change hands between rounds. Without it the checklist is ticked off with
+    /// nothing passed, to see what the exchange itself is worth.
+    pub exchange: bool,
+    /// How each seat plays, one per player.
+    pub seats: Vec<Difficulty>,
+    pub seed: u64,
+    /// Games played to points that go this long are given up on, since the target is out of
+    /// reach.
+    pub max_rounds: u16,
+}
+
+impl Settings {
+    /// Whether anyone can ever reach the target score of a game played to points: somebody has
+    /// to be able to gain points in a round.
+    pub fn target_reachable(&self) -> bool {
+        let target = match self.game_length {
+            GameLength::Points(target) => target,
+            GameLength::Rounds(_) => return true,
+        };
+        let players = self.seats.len();
+        let best = (0..players)
+            .map(|position| self.scoring.points(Rank::for_position(position, players)))
+            .max()
+            .unwrap_or(0);
+        target > 0 && (best > 0 || self.revolution_bonus > 0)
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            games: 1000,
+            game_length: GameLength::Rounds(5),
+            scoring: ScoringTable::default(),
+            bankruptcy: Bankruptcy::default(),
+            revolution_bonus: 0,
+            foul_finish: false,
+            deck: Deck::default(),
+            exchange: true,
+            seats: vec![Difficulty::default(); 4],
+            seed: 1,
+            max_rounds: MAX_ROUNDS,
+        }
+    }
+}
+
+/// Totals for one rank players held going into a round.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RankStats {
+    /// Rounds started with this rank.
+    pub rounds: u64,
+    /// Rounds that ended with the same rank again.
+    pub kept: u64,
+    pub points: i64,
+}
+
+impl RankStats {
+    pub fn persistence(&self) -> f64 {
+        ratio(self.kept, self.rounds)
+    }
+
+    pub fn average_points(&self) -> f64 {
+        self.points as f64 / self.rounds.max(1) as f64
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Stats {
+    pub games: u64,
+    pub rounds: u64,
+    /// Indexed by `rank_index`.
+    pub by_rank: [RankStats; 5],
+    /// Rounds in which a tycoon could have gone bankrupt: every round after the first.
+    pub bankruptcy_chances: u64,
+    pub bankruptcies: u64,
+    /// How often a game's winner was the first round's tycoon.
+    pub first_tycoon_wins: u64,
+}
+
+fn ratio(n: u64, d: u64) -> f64 {
+    n as f64 / d.max(1) as f64
+}
+
+fn rank_index(rank: Rank) -> usize {
+    match rank {
+        Rank::Tycoon => 0,
+        Rank::Rich => 1,
+        Rank::Commoner => 2,
+        Rank::Poor => 3,
+        Rank::Beggar => 4,
+    }
+}
+
+const RANKS: [Rank; 5] = [
+    Rank::Tycoon,
+    Rank::Rich,
+    Rank::Commoner,
+    Rank::Poor,
+    Rank::Beggar,
+];
+
+impl Stats {
+    pub fn bankruptcy_rate(&self) -> f64 {
+        ratio(self.bankruptcies, self.bankruptcy_chances)
+    }
+
+    /// Adds one finished round, given everyone's rank going into it.
+    fn add_round(&mut self, game: &Game, before: &[Option<Rank>], bankrupt: bool) {
+        self.rounds += 1;
+        if before.iter().any(Option::is_some) {
+            self.bankruptcy_chances += 1;
+            if bankrupt {
+                self.bankruptcies += 1;
+            }
+        }
+        for (player, start) in game.players.iter().zip(before.iter()) {
+            let start = match start {
+                Some(rank) => *rank,
+                None => continue,
+            };
+            let stats = &mut self.by_rank[rank_index(start)];
+            stats.rounds += 1;
+            if player.rank == Some(start) {
+                stats.kept += 1;
+            }
+            stats.points += i64::from(player.round_points.last().copied().unwrap_or(0));
+        }
+    }
+}
+
+/// The default for `Settings::max_rounds`.
+pub const MAX_ROUNDS: u16 = 10_000;
+
+/// Plays one game between bots to the end, the same way the app does: going out through
+/// `Table::play` and scoring through `Game::handle_end_round`.
+pub fn play_game(settings: &Settings, rng: &mut Shuffler, stats: &mut Stats) -> Result<(), String> {
+    let mut game = Game::new(settings.game_length, settings.scoring);
+    game.bankruptcy = settings.bankruptcy;
+    game.revolution_bonus = settings.revolution_bonus;
+    game.foul_finish = settings.foul_finish;
+    game.deck = settings.deck;
+    let names: Vec<String> = (1..=settings.seats.len())
+        .map(|i| format!("Bot {}", i))
+        .collect();
+    game.set_players(&names);
+    for (player, difficulty) in game.players.iter_mut().zip(settings.seats.iter()) {
+        player.bot = Some(*difficulty);
+    }
+
+    let mut first_tycoon = None;
+    while !game.is_game_over() {
+        if game.round > settings.max_rounds {
+            return Err(format!(
+                "nobody reached the target score in {} rounds",
+                settings.max_rounds
+            ));
+        }
+        let before: Vec<Option<Rank>> = game.players.iter().map(|p| p.rank).collect();
+        let mut table = Table::deal(&game, rng);
+        if settings.exchange {
+            bots::exchange_all(&mut table, &mut game);
+        } else {
+            game.confirm_all_exchanges();
+        }
+        game.start_round();
+        bots::play_bots(&mut table, &mut game, rng);
+        if !table.is_over() || !game.is_round_complete() {
+            return Err(format!("the bots got stuck in round {}", game.round));
+        }
+        let bankrupt = game.bankrupt.is_some();
+        game.handle_end_round();
+        stats.add_round(&game, &before, bankrupt);
+        if first_tycoon.is_none() {
+            first_tycoon = game.find_tycoon();
+        }
+    }
+    stats.games += 1;
+    let winners = game.standings();
+    if winners
+        .iter()
+        .any(|s| s.place == 1 && Some(s.pid) == first_tycoon)
+    {
+        stats.first_tycoon_wins += 1;
+    }
+    Ok(())
+}
+
+pub fn simulate(settings: &Settings) -> Result<Stats, String> {
+    let mut rng = Shuffler::new(settings.seed);
+    let mut stats = Stats::default();
+    for _ in 0..settings.games {
+        play_game(settings, &mut rng, &mut stats)?;
+    }
+    Ok(stats)
+}
+
+fn percent(rate: f64) -> String {
+    format!("{:.1}%", rate * 100.0)
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{} games, {} rounds", self.games, self.rounds)?;
+        writeln!(f)?;
+        writeln!(
+            f,
+            "{:<10} {:>8} {:>8} {:>12}",
+            "Start as", "Rounds", "Kept", "Avg points"
+        )?;
+        for rank in RANKS.iter() {
+            let stats = &self.by_rank[rank_index(*rank)];
+            if stats.rounds == 0 {
+                continue;
+            }
+            writeln!(
+                f,
+                "{:<10} {:>8} {:>8} {:>12.2}",
+                rank.to_string(),
+                stats.rounds,
+                percent(stats.persistence()),
+                stats.average_points()
+            )?;
+        }
+        writeln!(f)?;
+        writeln!(
+            f,
+            "Bankruptcies: {} in {} rounds ({})",
+            self.bankruptcies,
+            self.bankruptcy_chances,
+            percent(self.bankruptcy_rate())
+        )?;
+        writeln!(
+            f,
+            "The first round's tycoon won {} of {} games ({})",
+            self.first_tycoon_wins,
+            self.games,
+            percent(ratio(self.first_tycoon_wins, self.games))
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(games: usize) -> Settings {
+        Settings {
+            games,
+            ..Settings::default()
+        }
+    }
+
+    #[test]
+    fn every_round_is_counted() {
+        let stats = simulate(&settings(20)).unwrap();
+        assert_eq!(stats.games, 20);
+        assert_eq!(stats.rounds, 100);
+        // Nobody has a rank going into the first round
+        let ranked: u64 = stats.by_rank.iter().map(|r| r.rounds).sum();
+        assert_eq!(ranked, 4 * 80);
+        assert_eq!(stats.bankruptcy_chances, 80);
+        assert_eq!(stats.by_rank[rank_index(Rank::Tycoon)].rounds, 80);
+    }
+
+    #[test]
+    fn simulations_repeat_from_a_seed() {
+        assert_eq!(simulate(&settings(5)), simulate(&settings(5)));
+    }
+
+    #[test]
+    fn bankruptcy_can_be_turned_off() {
+        let stats = simulate(&Settings {
+            bankruptcy: Bankruptcy::Off,
+            ..settings(20)
+        })
+        .unwrap();
+        assert_eq!(stats.bankruptcies, 0);
+    }
+
+    #[test]
+    fn unreachable_targets_are_spotted() {
+        let mut settings = Settings {
+            game_length: GameLength::Points(10),
+            scoring: ScoringTable::parse("0/0/0/0/0").unwrap(),
+            ..settings(1)
+        };
+        assert!(!settings.target_reachable());
+        settings.revolution_bonus = 5;
+        assert!(settings.target_reachable());
+        settings.revolution_bonus = 0;
+        settings.game_length = GameLength::Rounds(3);
+        assert!(settings.target_reachable());
+    }
+
+    #[test]
+    fn endless_games_are_given_up_on() {
+        let settings = Settings {
+            game_length: GameLength::Points(10),
+            scoring: ScoringTable::parse("0/0/0/0/0").unwrap(),
+            max_rounds: 20,
+            ..settings(1)
+        };
+        assert_eq!(
+            simulate(&settings),
+            Err("nobody reached the target score in 20 rounds".to_string())
+        );
+    }
+
+    #[test]
+    fn reports_each_rank() {
+        let report = simulate(&settings(10)).unwrap().to_string();
+        assert!(report.starts_with("10 games, 50 rounds\n"));
+        assert!(report.contains("\nTycoon "));
+        assert!(report.contains("\nBeggar "));
+        assert!(report.contains("Bankruptcies: "));
+    }
+}
@@ -0,0 +1,335 @@
+use std::convert::TryFrom;
+use std::fmt;
+use tycoon_core::{
+    same_name, Bankruptcy, GameLength, ScoringTable, TieBreak, MAX_PLAYERS, MIN_PLAYERS,
+};
+
+/// A game written down as text, one line per round:
+///
+/// ```text
+/// # Friday night
+/// players: Alice, Bob, Carol, Dave
+/// rounds: 3
+/// scoring: 30/20/15/10/0
+/// Carol, Alice, Bob
+/// Alice, Dave, Bob, Carol
+/// ```
+///
+/// Each round lists players in the order they went out. The last player, and a tycoon who went
+/// bankrupt, get their rank automatically so they can be left off. `rounds:` (or `points:` to
+/// play to a target score) defaults to the number of rounds in the file, and `scoring:` takes
+/// either the name of a preset or points for Tycoon/Rich/Commoner/Poor/Beggar. `tie-break:` is
+/// one of `tycoons`, `last-round` or `shared` (the default), and `bankruptcy:` one of `off`,
+/// `beggar` (the default) or `drop-out`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transcript {
+    pub players: Vec<String>,
+    pub game_length: GameLength,
+    pub scoring: ScoringTable,
+    pub tie_break: TieBreak,
+    pub bankruptcy: Bankruptcy,
+    pub rounds: Vec<RoundLine>,
+}
+
+/// The finishing order for one round, and where it came from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoundLine {
+    pub line: usize,
+    pub order: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TranscriptError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl TranscriptError {
+    pub fn new(line: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for TranscriptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.line {
+            0 => f.write_str(&self.message),
+            line => write!(f, "line {}: {}", line, self.message),
+        }
+    }
+}
+
+impl std::error::Error for TranscriptError {}
+
+fn split_names(list: &str) -> Vec<String> {
+    list.split(',')
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+fn parse_scoring(value: &str, line: usize) -> Result<ScoringTable, TranscriptError> {
+    ScoringTable::parse(value).ok_or_else(|| {
+        TranscriptError::new(
+            line,
+            format!(
+                "scoring should be a preset or five numbers like 30/20/15/10/0, not {:?}",
+                value
+            ),
+        )
+    })
+}
+
+fn parse_number<T: std::str::FromStr>(value: &str, line: usize) -> Result<T, TranscriptError> {
+    match value.parse() {
+        Ok(n) => Ok(n),
+        Err(_) => Err(TranscriptError::new(
+            line,
+            format!("{:?} is not a number", value),
+        )),
+    }
+}
+
+pub fn parse(text: &str) -> Result<Transcript, TranscriptError> {
+    let mut players: Option<Vec<String>> = None;
+    let mut game_length = None;
+    let mut scoring = ScoringTable::default();
+    let mut tie_break = TieBreak::default();
+    let mut bankruptcy = Bankruptcy::default();
+    let mut rounds = vec![];
+
+    for (i, raw) in text.lines().enumerate() {
+        let line = i + 1;
+        let content = raw.split('#').next().unwrap_or("").trim();
+        if content.is_empty() {
+            continue;
+        }
+        let (key, value) = match content.find(':') {
+            Some(at) => (
+                content[..at].trim().to_lowercase(),
+                content[at + 1..].trim(),
+            ),
+            None => (String::new(), content),
+        };
+        match key.as_str() {
+            "players" => {
+                let names = split_names(value);
+                if names.len() < MIN_PLAYERS || names.len() > MAX_PLAYERS {
+                    return Err(TranscriptError::new(
+                        line,
+                        format!(
+                            "Tycoon needs between {} and {} players, not {}",
+                            MIN_PLAYERS,
+                            MAX_PLAYERS,
+                            names.len()
+                        ),
+                    ));
+                }
+                for (j, name) in names.iter().enumerate() {
+                    if names[..j].iter().any(|other| same_name(other, name)) {
+                        return Err(TranscriptError::new(
+                            line,
+                            format!("{} is listed twice", name),
+                        ));
+                    }
+                }
+                players = Some(names);
+            }
+            "rounds" => game_length = Some(GameLength::Rounds(parse_number(value, line)?)),
+            "points" => game_length = Some(GameLength::Points(parse_number(value, line)?)),
+            "scoring" => scoring = parse_scoring(value, line)?,
+            "tie-break" => {
+                tie_break = match TieBreak::from_key(&value.to_lowercase()) {
+                    Some(t) => t,
+                    None => {
+                        return Err(TranscriptError::new(
+                            line,
+                            format!(
+                                "tie-break should be tycoons, last-round or shared, not {:?}",
+                                value
+                            ),
+                        ))
+                    }
+                }
+            }
+            "bankruptcy" => {
+                bankruptcy = match Bankruptcy::from_key(&value.to_lowercase()) {
+                    Some(b) => b,
+                    None => {
+                        return Err(TranscriptError::new(
+                            line,
+                            format!(
+                                "bankruptcy should be off, beggar or drop-out, not {:?}",
+                                value
+                            ),
+                        ))
+                    }
+                }
+            }
+            "" | "round" => {
+                let known = match &players {
+                    Some(names) => names,
+                    None => {
+                        return Err(TranscriptError::new(
+                            line,
+                            "the players: line has to come before the first round",
+                        ))
+                    }
+                };
+                let order = split_names(value);
+                for (j, name) in order.iter().enumerate() {
+                    if !known.contains(name) {
+                        return Err(TranscriptError::new(
+                            line,
+                            format!("{} isn't one of the players", name),
+                        ));
+                    }
+                    if order[..j].contains(name) {
+                        return Err(TranscriptError::new(
+                            line,
+                            format!("{} went out twice", name),
+                        ));
+                    }
+                }
+                rounds.push(RoundLine { line, order });
+            }
+            other => {
+                return Err(TranscriptError::new(
+                    line,
+                    format!("unknown setting {:?}", other),
+                ))
+            }
+        }
+    }
+
+    let players = match players {
+        Some(names) => names,
+        None => {
+            return Err(TranscriptError::new(
+                0,
+                "the transcript has no players: line",
+            ))
+        }
+    };
+    let game_length = match game_length {
+        Some(length) => length,
+        None => match u8::try_from(rounds.len().max(1)) {
+            Ok(count) => GameLength::Rounds(count),
+            Err(_) => {
+                return Err(TranscriptError::new(
+                    rounds[usize::from(u8::MAX)].line,
+                    format!(
+                        "a game can't be longer than {} rounds, so give a points: target",
+                        u8::MAX
+                    ),
+                ))
+            }
+        },
+    };
+    Ok(Transcript {
+        players,
+        game_length,
+        scoring,
+        tie_break,
+        bankruptcy,
+        rounds,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_a_full_transcript() {
+        let transcript = parse(
+            "# Friday night\n\
+             players: Alice, Bob, Carol, Dave\n\
+             rounds: 3\n\
+             scoring: 3/2/1/1/0\n\
+             \n\
+             Carol, Alice, Bob\n\
+             round: Alice, Dave # Carol went bankrupt\n",
+        )
+        .unwrap();
+        assert_eq!(transcript.players, vec!["Alice", "Bob", "Carol", "Dave"]);
+        assert_eq!(transcript.game_length, GameLength::Rounds(3));
+        assert_eq!(transcript.scoring.tycoon, 3);
+        assert_eq!(transcript.scoring.beggar, 0);
+        assert_eq!(transcript.rounds.len(), 2);
+        assert_eq!(transcript.rounds[1].line, 7);
+        assert_eq!(transcript.rounds[1].order, vec!["Alice", "Dave"]);
+    }
+
+    #[test]
+    fn defaults_to_the_rounds_in_the_file() {
+        let transcript = parse("players: A, B, C\nA, B\nB, C\n").unwrap();
+        assert_eq!(transcript.game_length, GameLength::Rounds(2));
+        assert_eq!(transcript.scoring, ScoringTable::default());
+    }
+
+    #[test]
+    fn scoring_presets_by_name() {
+        let transcript = parse("players: A, B, C\nscoring: plus\n").unwrap();
+        assert_eq!(transcript.scoring.beggar, -1);
+        assert_eq!(parse("players: A, B, C\nscoring:  \n").unwrap_err().line, 2);
+    }
+
+    #[test]
+    fn rule_settings() {
+        let transcript =
+            parse("players: A, B, C\nbankruptcy: Drop-Out\ntie-break: last-round\n").unwrap();
+        assert_eq!(transcript.bankruptcy, Bankruptcy::DropOut);
+        assert_eq!(transcript.tie_break, TieBreak::LastRound);
+        assert_eq!(
+            parse("players: A, B, C\nbankruptcy: maybe\n")
+                .unwrap_err()
+                .line,
+            2
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_players() {
+        let error = parse("players: A, B, C\nA, Zed\n").unwrap_err();
+        assert_eq!(
+            error,
+            TranscriptError::new(2, "Zed isn't one of the players")
+        );
+    }
+
+    #[test]
+    fn rejects_bad_player_counts() {
+        assert_eq!(parse("players: A, B\n").unwrap_err().line, 1);
+    }
+
+    #[test]
+    fn rejects_names_listed_twice() {
+        let error = parse("players: Alice, Bob, alice \n").unwrap_err();
+        assert_eq!(error, TranscriptError::new(1, "alice is listed twice"));
+    }
+
+    #[test]
+    fn long_transcripts_need_a_length() {
+        let text = format!("players: A, B, C\n{}", "A, B\n".repeat(300));
+        let error = parse(&text).unwrap_err();
+        assert_eq!(error.line, 257);
+        assert!(error.message.contains("points:"));
+        let text = format!("players: A, B, C\npoints: 1000\n{}", "A, B\n".repeat(300));
+        assert_eq!(parse(&text).unwrap().rounds.len(), 300);
+    }
+
+    #[test]
+    fn rejects_rounds_before_players() {
+        assert_eq!(parse("A, B\nplayers: A, B, C\n").unwrap_err().line, 1);
+    }
+
+    #[test]
+    fn rejects_going_out_twice() {
+        let error = parse("players: A, B, C\nA, A\n").unwrap_err();
+        assert_eq!(error.message, "A went out twice");
+    }
+}
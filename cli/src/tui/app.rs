@@ -0,0 +1,290 @@
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use tycoon_core::{
+    check_names, same_name, Game, GameLength, PlayerOrder, ScoringTable, MAX_PLAYERS,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Screen {
+    Setup,
+    Playing,
+    GameOver,
+}
+
+/// The player list and rules being typed in before a game.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SetupForm {
+    pub names: Vec<String>,
+    pub input: String,
+    pub game_length: GameLength,
+    /// Index into `ScoringTable::presets`.
+    pub scoring: usize,
+    pub error: Option<String>,
+}
+
+impl SetupForm {
+    pub fn scoring_table(&self) -> (&'static str, ScoringTable) {
+        ScoringTable::presets()[self.scoring]
+    }
+
+    fn add_name(&mut self) {
+        let name = self.input.trim().to_string();
+        if name.is_empty() {
+            self.error = Some("Every player needs a name.".into());
+        } else if self.names.len() == MAX_PLAYERS {
+            self.error = Some(format!("Tycoon is for at most {} players.", MAX_PLAYERS));
+        } else if self.names.iter().any(|other| same_name(other, &name)) {
+            self.error = Some(format!("{} is already playing.", name));
+        } else {
+            self.names.push(name);
+            self.input.clear();
+            self.error = None;
+        }
+    }
+
+    fn change_length(&mut self, up: bool) {
+        self.game_length = match self.game_length {
+            GameLength::Rounds(n) if up => GameLength::Rounds(n.saturating_add(1)),
+            GameLength::Rounds(n) => GameLength::Rounds(n.saturating_sub(1).max(1)),
+            GameLength::Points(n) if up => GameLength::Points(n.saturating_add(10)),
+            GameLength::Points(n) => GameLength::Points(n.saturating_sub(10).max(10)),
+        };
+    }
+
+    fn toggle_length_kind(&mut self) {
+        let kind = match self.game_length {
+            GameLength::Rounds(_) => "points",
+            GameLength::Points(_) => "rounds",
+        };
+        self.game_length = self.game_length.with_kind(kind);
+    }
+}
+
+/// Everything the terminal scorer shows, driven one key press at a time.
+pub struct App {
+    pub game: Game,
+    pub screen: Screen,
+    pub setup: SetupForm,
+    /// How the score table is sorted. The Go Out keys always follow seating order.
+    pub order: PlayerOrder,
+    pub quit: bool,
+}
+
+impl Default for App {
+    fn default() -> Self {
+        Self {
+            game: Game::default(),
+            screen: Screen::Setup,
+            setup: SetupForm::default(),
+            order: PlayerOrder::default(),
+            quit: false,
+        }
+    }
+}
+
+impl App {
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.quit = true;
+            return;
+        }
+        match self.screen {
+            Screen::Setup => self.setup_key(key),
+            Screen::Playing => self.playing_key(key),
+            Screen::GameOver => self.game_over_key(key),
+        }
+    }
+
+    fn start_setup(&mut self) {
+        self.setup = SetupForm {
+            names: self.game.players.iter().map(|p| p.name.clone()).collect(),
+            game_length: self.game.game_length,
+            scoring: ScoringTable::presets()
+                .iter()
+                .position(|(_, table)| *table == self.game.scoring)
+                .unwrap_or(0),
+            ..SetupForm::default()
+        };
+        self.screen = Screen::Setup;
+    }
+
+    fn setup_key(&mut self, key: KeyEvent) {
+        let form = &mut self.setup;
+        match key.code {
+            KeyCode::Char(c) => form.input.push(c),
+            KeyCode::Backspace if form.input.is_empty() => {
+                form.names.pop();
+            }
+            KeyCode::Backspace => {
+                form.input.pop();
+            }
+            KeyCode::Enter if !form.input.trim().is_empty() => form.add_name(),
+            KeyCode::Enter => {
+                if let Err(error) = check_names(&form.names) {
+                    form.error = Some(error);
+                    return;
+                }
+                let (_, scoring) = form.scoring_table();
+                self.game = Game::new(form.game_length, scoring);
+                self.game.set_players(&form.names);
+                self.screen = Screen::Playing;
+            }
+            KeyCode::Up => form.change_length(true),
+            KeyCode::Down => form.change_length(false),
+            KeyCode::Tab => form.toggle_length_kind(),
+            KeyCode::Right => form.scoring = (form.scoring + 1) % ScoringTable::presets().len(),
+            KeyCode::Left => {
+                let presets = ScoringTable::presets().len();
+                form.scoring = (form.scoring + presets - 1) % presets;
+            }
+            KeyCode::Esc => {
+                if self.game.players.is_empty() {
+                    self.quit = true;
+                } else {
+                    self.screen = Screen::Playing;
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn playing_key(&mut self, key: KeyEvent) {
+        match key.code {
+            // Between rounds the number keys tick off the card exchanges instead
+            KeyCode::Char(c @ '1'..='9') if self.game.exchanging => {
+                let index = c as usize - '1' as usize;
+                if let Some(exchange) = self.game.exchanges.get(index) {
+                    let done = !exchange.done;
+                    self.game.confirm_exchange(index, done);
+                }
+            }
+            KeyCode::Char('r') | KeyCode::Enter if self.game.exchanging => self.game.start_round(),
+            KeyCode::Char(c @ '1'..='9') => {
+                self.game.handle_go_out(c as usize - '1' as usize);
+            }
+            KeyCode::Char('e') if self.game.is_round_complete() => {
+                self.game.handle_end_round();
+                if self.game.is_game_over() {
+                    self.screen = Screen::GameOver;
+                }
+            }
+            KeyCode::Char('n') => self.game.new_game(),
+            KeyCode::Char('o') => {
+                let orders = PlayerOrder::all();
+                let next = orders.iter().position(|o| *o == self.order).unwrap_or(0) + 1;
+                self.order = orders[next % orders.len()];
+            }
+            KeyCode::Char('s') => self.start_setup(),
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            _ => (),
+        }
+    }
+
+    fn game_over_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char('n') => {
+                self.game.new_game();
+                self.screen = Screen::Playing;
+            }
+            KeyCode::Char('s') => self.start_setup(),
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            _ => (),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tycoon_core::Rank;
+
+    fn press(app: &mut App, code: KeyCode) {
+        app.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    fn type_text(app: &mut App, text: &str) {
+        for c in text.chars() {
+            press(app, KeyCode::Char(c));
+        }
+    }
+
+    fn four_player_app() -> App {
+        let mut app = App::default();
+        for name in ["Alice", "Bob", "Carol", "Dave"].iter() {
+            type_text(&mut app, name);
+            press(&mut app, KeyCode::Enter);
+        }
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Enter);
+        app
+    }
+
+    #[test]
+    fn setup_starts_a_game() {
+        let app = four_player_app();
+        assert_eq!(app.screen, Screen::Playing);
+        assert_eq!(app.game.game_length, GameLength::Rounds(2));
+        let names: Vec<&str> = app.game.players.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["Alice", "Bob", "Carol", "Dave"]);
+    }
+
+    #[test]
+    fn setup_needs_enough_unique_players() {
+        let mut app = App::default();
+        type_text(&mut app, "Alice");
+        press(&mut app, KeyCode::Enter);
+        type_text(&mut app, "alice ");
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.setup.names, vec!["Alice"]);
+        assert!(app.setup.error.is_some());
+        app.setup.input = " ".into();
+        app.setup.add_name();
+        assert_eq!(app.setup.names, vec!["Alice"]);
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.screen, Screen::Setup);
+    }
+
+    #[test]
+    fn keys_go_out_by_seat_and_end_the_round() {
+        let mut app = four_player_app();
+        type_text(&mut app, "e");
+        assert_eq!(app.game.round, 1);
+        type_text(&mut app, "321e");
+        assert_eq!(app.game.round, 2);
+        assert_eq!(app.game.players[2].rank, Some(Rank::Tycoon));
+        assert_eq!(app.game.players[3].rank, Some(Rank::Beggar));
+        // The round can't start until both exchanges are ticked off
+        type_text(&mut app, "1r");
+        assert!(app.game.exchanging);
+        type_text(&mut app, "2r");
+        assert!(!app.game.exchanging);
+        // Bankruptcy comes from the same rules as everywhere else
+        type_text(&mut app, "1");
+        assert!(app.game.beggar_message.is_some());
+        type_text(&mut app, "2e");
+        assert_eq!(app.screen, Screen::GameOver);
+        type_text(&mut app, "n");
+        assert_eq!(app.screen, Screen::Playing);
+        assert_eq!(app.game.round, 1);
+    }
+
+    #[test]
+    fn setup_keeps_the_current_players() {
+        let mut app = four_player_app();
+        type_text(&mut app, "s");
+        assert_eq!(app.screen, Screen::Setup);
+        assert_eq!(app.setup.names.len(), 4);
+        press(&mut app, KeyCode::Esc);
+        assert_eq!(app.screen, Screen::Playing);
+    }
+
+    #[test]
+    fn sorting_cycles_and_keeps_the_seat_keys() {
+        let mut app = four_player_app();
+        type_text(&mut app, "o");
+        assert_eq!(app.order, PlayerOrder::Score);
+        type_text(&mut app, "oo");
+        assert_eq!(app.order, PlayerOrder::Seat);
+        type_text(&mut app, "o4");
+        assert!(app.game.players_out.contains_key(&3));
+    }
+}
@@ -0,0 +1,32 @@
+//! An interactive scorer for the terminal, with the same screens as the web app: setup, the score
+//! table with a Go Out key for each seat, and the summary once the game is over.
+
+use std::io;
+
+use ratatui::crossterm::event::{self, Event, KeyEventKind};
+use ratatui::DefaultTerminal;
+
+mod app;
+mod view;
+
+use app::App;
+
+pub fn run() -> io::Result<()> {
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+fn event_loop(terminal: &mut DefaultTerminal) -> io::Result<()> {
+    let mut app = App::default();
+    while !app.quit {
+        terminal.draw(|frame| view::draw(frame, &app))?;
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press {
+                app.handle_key(key);
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,351 @@
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table, Wrap};
+use ratatui::Frame;
+use tycoon_core::{exchange, Rank, ScoringTable};
+
+use super::app::{App, Screen};
+
+fn rank_string(rank: Option<Rank>) -> String {
+    match rank {
+        Some(r) => r.to_string(),
+        None => "None".into(),
+    }
+}
+
+fn help_line(keys: &[(&str, &str)]) -> Line<'static> {
+    let mut spans = vec![];
+    for (key, action) in keys {
+        spans.push(Span::styled(key.to_string(), Style::new().bold()));
+        spans.push(Span::raw(format!(" {}   ", action)));
+    }
+    Line::from(spans).fg(Color::DarkGray)
+}
+
+pub fn draw(frame: &mut Frame, app: &App) {
+    let [header, body, help] = Layout::vertical([
+        Constraint::Length(2),
+        Constraint::Min(0),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+    frame.render_widget(
+        Paragraph::new("Tycoon Scorer")
+            .bold()
+            .centered()
+            .block(Block::new().borders(Borders::BOTTOM)),
+        header,
+    );
+    match app.screen {
+        Screen::Setup => {
+            setup_screen(frame, app, body);
+            frame.render_widget(
+                help_line(&[
+                    ("Enter", "add player / start"),
+                    ("Up/Down", "length"),
+                    ("Tab", "rounds or points"),
+                    ("Left/Right", "scoring"),
+                    ("Esc", "cancel"),
+                ]),
+                help,
+            );
+        }
+        Screen::Playing if app.game.exchanging => {
+            playing_screen(frame, app, body);
+            let keys = match app.game.exchanges.len() {
+                1 => "1".to_string(),
+                count => format!("1-{}", count),
+            };
+            frame.render_widget(
+                help_line(&[
+                    (&keys, "exchange done"),
+                    ("r", "start round"),
+                    ("o", "sort"),
+                    ("n", "new game"),
+                    ("s", "setup"),
+                    ("q", "quit"),
+                ]),
+                help,
+            );
+        }
+        Screen::Playing => {
+            playing_screen(frame, app, body);
+            frame.render_widget(
+                help_line(&[
+                    ("1-8", "go out"),
+                    ("e", "end round"),
+                    ("o", "sort"),
+                    ("n", "new game"),
+                    ("s", "setup"),
+                    ("q", "quit"),
+                ]),
+                help,
+            );
+        }
+        Screen::GameOver => {
+            game_over_screen(frame, app, body);
+            frame.render_widget(
+                help_line(&[("n", "new game"), ("s", "setup"), ("q", "quit")]),
+                help,
+            );
+        }
+    }
+}
+
+fn setup_screen(frame: &mut Frame, app: &App, area: Rect) {
+    let form = &app.setup;
+    let (preset, scoring) = form.scoring_table();
+    let mut lines = vec![Line::from("Add Players").bold().centered(), Line::from("")];
+    if let Some(error) = &form.error {
+        lines.push(Line::from(error.as_str()).red().bold());
+    }
+    for (i, name) in form.names.iter().enumerate() {
+        lines.push(Line::from(format!("  {}. {}", i + 1, name)));
+    }
+    lines.push(Line::from(vec![
+        Span::raw(format!("  {}. ", form.names.len() + 1)),
+        Span::styled(form.input.clone(), Style::new().fg(Color::Blue)),
+        Span::raw("_"),
+    ]));
+    lines.push(Line::from(""));
+    lines.push(Line::from(format!(
+        "Game length: {} {}",
+        form.game_length.value(),
+        form.game_length.kind()
+    )));
+    lines.push(Line::from(format!(
+        "Scoring: {} ({})",
+        preset,
+        points_text(&scoring)
+    )));
+    frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), area);
+}
+
+fn points_text(scoring: &ScoringTable) -> String {
+    [
+        Rank::Tycoon,
+        Rank::Rich,
+        Rank::Commoner,
+        Rank::Poor,
+        Rank::Beggar,
+    ]
+    .iter()
+    .map(|rank| format!("{} {}", rank, scoring.points(*rank)))
+    .collect::<Vec<String>>()
+    .join(", ")
+}
+
+fn playing_screen(frame: &mut Frame, app: &App, area: Rect) {
+    let game = &app.game;
+    let [round, table, message, instructions] = Layout::vertical([
+        Constraint::Length(2),
+        Constraint::Length(game.players.len() as u16 + 2),
+        Constraint::Length(2),
+        Constraint::Min(0),
+    ])
+    .areas(area);
+
+    frame.render_widget(
+        Paragraph::new(vec![
+            Line::from(game.game_length.round_label(game.round))
+                .bold()
+                .fg(Color::Blue),
+            Line::from(format!("Sorted by {}", app.order.label().to_lowercase()))
+                .fg(Color::DarkGray),
+        ])
+        .centered(),
+        round,
+    );
+
+    let rows = game.player_order(app.order).into_iter().map(|pid| {
+        let player = &game.players[pid];
+        let status = match game.players_out.get(&pid) {
+            Some(rank) => Cell::from(format!("out ({})", rank)).fg(Color::DarkGray),
+            None if game.exchanging => Cell::from(""),
+            None => Cell::from(format!("[{}] Go Out", pid + 1)).fg(Color::Green),
+        };
+        Row::new(vec![
+            Cell::from(player.name.clone()),
+            Cell::from(player.score.to_string()),
+            Cell::from(rank_string(player.rank)),
+            status,
+        ])
+    });
+    frame.render_widget(
+        Table::new(
+            rows,
+            [
+                Constraint::Min(16),
+                Constraint::Length(8),
+                Constraint::Length(10),
+                Constraint::Length(16),
+            ],
+        )
+        .header(
+            Row::new(vec!["Player Name", "Score", "Rank", ""])
+                .style(Style::new().add_modifier(Modifier::BOLD).fg(Color::Blue))
+                .bottom_margin(1),
+        ),
+        table,
+    );
+
+    let status = match &game.beggar_message {
+        Some(text) => Line::from(text.as_str()).red().bold(),
+        None if game.exchanging && game.is_exchange_complete() => {
+            Line::from("Press r to start the round.")
+        }
+        None if game.exchanging => Line::from("Swap cards before the round starts.").yellow(),
+        None if game.is_round_complete() => Line::from("Press e to end the round."),
+        None => Line::from(""),
+    };
+    frame.render_widget(Paragraph::new(status).centered(), message);
+
+    if game.round != 1 {
+        let deal = game.deal();
+        let mut lines = vec![
+            Line::from("Card exchange").bold(),
+            Line::from(deal.describe(&game.players)).fg(Color::DarkGray),
+        ];
+        for instruction in exchange::instructions(&game.players) {
+            lines.push(Line::from(
+                instruction.describe_for_deal(&game.players, &deal),
+            ));
+        }
+        if game.exchanging {
+            lines.push(Line::from(""));
+            for (i, exchange) in game.exchanges.iter().enumerate() {
+                let mark = if exchange.done { "x" } else { " " };
+                lines.push(Line::from(format!(
+                    "[{}] [{}] {}",
+                    i + 1,
+                    mark,
+                    exchange.describe(&game.players)
+                )));
+            }
+        }
+        frame.render_widget(
+            Paragraph::new(lines).wrap(Wrap { trim: false }),
+            instructions,
+        );
+    }
+}
+
+fn game_over_screen(frame: &mut Frame, app: &App, area: Rect) {
+    let game = &app.game;
+    let standings = game.standings();
+    let winners: Vec<&str> = standings
+        .iter()
+        .filter(|s| s.place == 1)
+        .map(|s| game.players[s.pid].name.as_str())
+        .collect();
+    let title = match winners.len() {
+        1 => "WINNER",
+        _ => "WINNERS",
+    };
+    let mut lines = vec![
+        Line::from(format!("{}: {}", title, winners.join(" & ")))
+            .bold()
+            .fg(Color::Green)
+            .centered(),
+        Line::from(""),
+    ];
+    for standing in standings.iter() {
+        let player = &game.players[standing.pid];
+        let ranks: Vec<String> = player
+            .past_ranks
+            .iter()
+            .chain(player.rank.iter())
+            .map(|r| r.to_string())
+            .collect();
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!(
+                    "{:<10} {:<16} {:>6}  ",
+                    standing.place_text(),
+                    player.name,
+                    player.score
+                ),
+                Style::new().bold(),
+            ),
+            Span::raw(ranks.join(", ")),
+        ]));
+    }
+    frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+    use tycoon_core::GameLength;
+
+    fn screen_text(app: &App) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(100, 24)).unwrap();
+        terminal.draw(|frame| draw(frame, app)).unwrap();
+        let buffer = terminal.backend().buffer();
+        buffer
+            .content()
+            .chunks(buffer.area.width as usize)
+            .map(|row| row.iter().map(|cell| cell.symbol()).collect::<String>())
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn shows_the_score_table_and_exchange() {
+        let mut app = App::default();
+        app.game.set_players(&["Alice", "Bob", "Carol", "Dave"]);
+        app.screen = Screen::Playing;
+        for pid in 0..3 {
+            app.game.handle_go_out(pid);
+        }
+        app.game.handle_end_round();
+        let text = screen_text(&app);
+        assert!(text.contains("Round 2 of 3"));
+        assert!(text.contains("[1] [ ] Alice and Dave swap two cards"));
+        assert!(text.contains("1-2 exchange done"));
+        assert!(!text.contains("Go Out"));
+        assert!(text.contains("Alice: passes two cards of their choice to Dave"));
+        assert!(text.contains("the 1 card left over goes to Dave."));
+        assert!(text.contains(
+            "Dave: passes their two highest cards to Alice, and receives the first extra card."
+        ));
+
+        app.game.confirm_exchange(0, true);
+        app.game.confirm_exchange(1, true);
+        app.game.start_round();
+        let text = screen_text(&app);
+        assert!(text.contains("[4] Go Out"));
+    }
+
+    #[test]
+    fn three_players_have_one_exchange_key() {
+        let mut app = App::default();
+        app.game.set_players(&["Alice", "Bob", "Carol"]);
+        app.screen = Screen::Playing;
+        app.game.handle_go_out(0);
+        app.game.handle_go_out(1);
+        app.game.handle_end_round();
+        let text = screen_text(&app);
+        assert!(text.contains("1 exchange done"));
+        assert!(!text.contains("1-"));
+    }
+
+    #[test]
+    fn game_over_lists_every_rounds_rank() {
+        let mut app = App::default();
+        app.game.game_length = GameLength::Rounds(1);
+        app.game.set_players(&["Alice", "Bob", "Carol"]);
+        app.game.handle_go_out(2);
+        app.game.handle_go_out(0);
+        app.game.handle_end_round();
+        app.screen = Screen::GameOver;
+        let text = screen_text(&app);
+        assert!(text.contains("WINNER: Carol"));
+        // The only round played is the last one
+        assert!(text.contains("Tycoon"));
+        assert!(text.contains("Beggar"));
+    }
+}
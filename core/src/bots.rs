@@ -0,0 +1,307 @@
+//! Computer players for practice games. Each picks a play from `Table::legal_plays`, so they play
+//! by exactly the same rules as everyone else.
+
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::HashSet;
+
+use crate::cards::{Card, Shuffler};
+use crate::engine::{Play, PlayError, Table};
+use crate::game::Game;
+
+/// How well a computer player plays.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum Difficulty {
+    /// Any legal play, or a pass, at random.
+    Random,
+    /// Always gets rid of its weakest cards first.
+    #[default]
+    Greedy,
+    /// Plans how to empty its hand in as few plays as it can, saving its best cards.
+    Lookahead,
+}
+
+impl Difficulty {
+    pub fn all() -> [Self; 3] {
+        [Self::Random, Self::Greedy, Self::Lookahead]
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Random => "Beginner (random)",
+            Self::Greedy => "Intermediate (greedy)",
+            Self::Lookahead => "Expert (lookahead)",
+        }
+    }
+
+    /// What the setup form and the simulator's `--bots` option call this bot.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::Random => "random",
+            Self::Greedy => "greedy",
+            Self::Lookahead => "lookahead",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::all().iter().copied().find(|d| d.key() == key)
+    }
+
+    /// The play to make, or `None` to pass.
+    pub fn choose(
+        &self,
+        table: &Table,
+        game: &Game,
+        pid: usize,
+        rng: &mut Shuffler,
+    ) -> Option<Play> {
+        let plays = table.legal_plays(pid);
+        match self {
+            Self::Random => {
+                // Passing is one more choice, unless this player has to start the pile
+                let choices = plays.len() + usize::from(table.top().is_some());
+                let choice = rng.below(choices);
+                plays.into_iter().nth(choice)
+            }
+            Self::Greedy => plays
+                .into_iter()
+                .min_by_key(|play| (weakness(play, table.reversed), Reverse(play.cards.len()))),
+            Self::Lookahead => lookahead(table, game, pid, plays),
+        }
+    }
+}
+
+/// Lower for plays that are weaker the way the cards currently run.
+fn weakness(play: &Play, reversed: bool) -> u8 {
+    if reversed && !play.cards[0].is_joker() {
+        u8::MAX - play.strength
+    } else {
+        play.strength
+    }
+}
+
+/// Roughly how many plays it would take to get rid of a hand: one for each value, and one for
+/// each joker.
+fn plays_needed(hand: &[Card]) -> usize {
+    let values: HashSet<u8> = hand.iter().filter_map(Card::value).collect();
+    values.len() + hand.iter().filter(|c| c.is_joker()).count()
+}
+
+fn remove_cards(hand: &[Card], cards: &[Card]) -> Vec<Card> {
+    let mut rest = hand.to_vec();
+    for card in cards.iter() {
+        if let Some(i) = rest.iter().position(|c| c == card) {
+            rest.remove(i);
+        }
+    }
+    rest
+}
+
+fn lookahead(table: &Table, game: &Game, pid: usize, plays: Vec<Play>) -> Option<Play> {
+    let hand = &table.hands[pid];
+    let is_foul = |play: &Play| game.foul_finish && play.cards.iter().any(Card::is_foul_finish);
+    // Going out now is always best, as long as it isn't a foul
+    if let Some(play) = plays
+        .iter()
+        .find(|play| play.cards.len() == hand.len() && !is_foul(play))
+    {
+        return Some(play.clone());
+    }
+    let cost = |play: &Play| {
+        let rest = remove_cards(hand, &play.cards);
+        let needed = plays_needed(&rest);
+        let mut cost = needed * 10;
+        // Splitting up a pair or triple leaves more plays to make later
+        let value = play.cards[0].value();
+        if value.is_some() && rest.iter().any(|c| c.value() == value) {
+            cost += 15;
+        }
+        // Save jokers and 2s for when they can win the pile near the end
+        let top = play.cards[0].is_joker() || play.strength >= Card::TWO;
+        if top && needed > 2 {
+            cost += 40;
+        }
+        // Don't get stuck with only cards that are fouls to go out on
+        if game.foul_finish && !rest.is_empty() && rest.iter().all(Card::is_foul_finish) {
+            cost += 50;
+        }
+        cost * 256 + usize::from(weakness(play, table.reversed))
+    };
+    let best = plays.into_iter().min_by_key(|play| cost(play))?;
+    // Rather than waste a strong card on someone else's pile, wait for the next one
+    let wasteful = best.cards[0].is_joker() || best.strength >= Card::ACE;
+    if table.top().is_some() && wasteful && plays_needed(hand) > 3 {
+        return None;
+    }
+    Some(best)
+}
+
+/// The weakest cards a player can hand over, which is what bots give away in an exchange.
+pub fn weakest_cards(table: &Table, pid: usize, count: usize) -> Vec<Card> {
+    table.hands[pid].iter().take(count).copied().collect()
+}
+
+/// Takes one turn for the player whose turn it is, using `difficulty`.
+pub fn take_turn(
+    difficulty: Difficulty,
+    table: &mut Table,
+    game: &mut Game,
+    rng: &mut Shuffler,
+) -> Result<(), PlayError> {
+    let pid = table.turn;
+    match difficulty.choose(table, game, pid, rng) {
+        Some(play) => table.play(game, pid, &play.cards),
+        None => table.pass(game, pid),
+    }
+}
+
+/// Lets the computer players take their turns until it's a person's turn or the round is over.
+pub fn play_bots(table: &mut Table, game: &mut Game, rng: &mut Shuffler) {
+    while !table.is_over() {
+        let difficulty = match game.players.get(table.turn).and_then(|p| p.bot) {
+            Some(difficulty) => difficulty,
+            None => return,
+        };
+        if take_turn(difficulty, table, game, rng).is_err() {
+            return;
+        }
+    }
+}
+
+/// Makes every exchange on the game's checklist, each side handing over its weakest or highest
+/// cards, and writes down what was passed.
+pub fn exchange_all(table: &mut Table, game: &mut Game) {
+    for i in 0..game.exchanges.len() {
+        let exchange = game.exchanges[i].clone();
+        let chosen = weakest_cards(table, exchange.upper, exchange.count);
+        if let Ok(returned) = table.exchange(&exchange, &chosen) {
+            game.record_exchange_cards(i, true, &cards_text(&chosen));
+            game.record_exchange_cards(i, false, &cards_text(&returned));
+        }
+    }
+    game.confirm_all_exchanges();
+}
+
+pub fn cards_text(cards: &[Card]) -> String {
+    let cards: Vec<String> = cards.iter().map(|c| c.to_string()).collect();
+    cards.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::Suit;
+    use crate::player::Rank;
+
+    fn bot_game(difficulty: Difficulty) -> Game {
+        let mut game = Game::default();
+        game.set_players(&["A", "B", "C", "D"]);
+        for player in game.players.iter_mut() {
+            player.bot = Some(difficulty);
+        }
+        game
+    }
+
+    #[test]
+    fn bots_play_whole_games() {
+        for difficulty in Difficulty::all().iter() {
+            let mut game = bot_game(*difficulty);
+            let mut rng = Shuffler::new(42);
+            while !game.is_game_over() {
+                let mut table = Table::deal(&game, &mut rng);
+                exchange_all(&mut table, &mut game);
+                game.start_round();
+                play_bots(&mut table, &mut game, &mut rng);
+                assert!(table.is_over(), "{:?} got stuck", difficulty);
+                game.handle_end_round();
+            }
+            assert_eq!(game.round_records.len(), 3);
+        }
+    }
+
+    #[test]
+    fn bots_stop_for_people() {
+        let mut game = bot_game(Difficulty::Greedy);
+        game.players[2].bot = None;
+        let mut rng = Shuffler::new(1);
+        let mut table = Table::deal(&game, &mut rng);
+        table.turn = 0;
+        play_bots(&mut table, &mut game, &mut rng);
+        assert_eq!(table.turn, 2);
+    }
+
+    #[test]
+    fn greedy_plays_its_weakest_cards() {
+        let game = bot_game(Difficulty::Greedy);
+        let table = Table::new(
+            vec![vec![
+                Card::new(9, Suit::Clubs),
+                Card::new(4, Suit::Hearts),
+                Card::new(4, Suit::Spades),
+                Card::Joker,
+            ]],
+            0,
+        );
+        let play = Difficulty::Greedy
+            .choose(&table, &game, 0, &mut Shuffler::new(0))
+            .unwrap();
+        assert_eq!(play.cards.len(), 2);
+        assert_eq!(play.strength, 4);
+    }
+
+    #[test]
+    fn lookahead_goes_out_when_it_can() {
+        let game = bot_game(Difficulty::Lookahead);
+        let mut table = Table::new(
+            vec![
+                vec![Card::new(7, Suit::Clubs), Card::new(7, Suit::Hearts)],
+                vec![Card::new(5, Suit::Clubs), Card::new(5, Suit::Diamonds)],
+            ],
+            0,
+        );
+        table.pile.push((1, Play::new(&table.hands[1]).unwrap()));
+        let play = Difficulty::Lookahead
+            .choose(&table, &game, 0, &mut Shuffler::new(0))
+            .unwrap();
+        assert_eq!(play.cards.len(), 2);
+    }
+
+    #[test]
+    fn lookahead_saves_its_jokers() {
+        let game = bot_game(Difficulty::Lookahead);
+        let mut table = Table::new(
+            vec![
+                vec![
+                    Card::new(3, Suit::Clubs),
+                    Card::new(5, Suit::Clubs),
+                    Card::new(7, Suit::Clubs),
+                    Card::new(9, Suit::Clubs),
+                    Card::Joker,
+                ],
+                vec![Card::new(Card::ACE, Suit::Spades)],
+            ],
+            0,
+        );
+        table.pile.push((1, Play::new(&table.hands[1]).unwrap()));
+        assert_eq!(
+            Difficulty::Lookahead.choose(&table, &game, 0, &mut Shuffler::new(0)),
+            None
+        );
+    }
+
+    #[test]
+    fn exchanges_are_made_and_confirmed() {
+        let mut game = bot_game(Difficulty::Greedy);
+        game.handle_go_out(0);
+        game.handle_go_out(1);
+        game.handle_go_out(2);
+        game.handle_end_round();
+        let mut table = Table::deal(&game, &mut Shuffler::new(3));
+        let beggar_best = table.highest_cards(3, 2);
+        exchange_all(&mut table, &mut game);
+        assert!(game.is_exchange_complete());
+        assert_eq!(game.exchanges[0].lower_cards, cards_text(&beggar_best));
+        assert_eq!(game.players[3].rank, Some(Rank::Beggar));
+        assert!(beggar_best.iter().all(|c| table.hands[0].contains(c)));
+    }
+}
@@ -0,0 +1,197 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::deal::Deck;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Suit {
+    Clubs,
+    Diamonds,
+    Hearts,
+    Spades,
+}
+
+impl Suit {
+    pub fn all() -> [Self; 4] {
+        [Self::Clubs, Self::Diamonds, Self::Hearts, Self::Spades]
+    }
+
+    pub fn symbol(&self) -> char {
+        match self {
+            Self::Clubs => '♣',
+            Self::Diamonds => '♦',
+            Self::Hearts => '♥',
+            Self::Spades => '♠',
+        }
+    }
+}
+
+/// A playing card. `value` runs from 3, the weakest card, up through J (11), Q (12), K (13) and
+/// A (14) to 2 (15), the strongest, which is the order Tycoon ranks them in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Card {
+    Suited { value: u8, suit: Suit },
+    Joker,
+}
+
+impl Card {
+    pub const LOWEST: u8 = 3;
+    pub const ACE: u8 = 14;
+    pub const TWO: u8 = 15;
+
+    pub fn new(value: u8, suit: Suit) -> Self {
+        Self::Suited { value, suit }
+    }
+
+    pub fn value(&self) -> Option<u8> {
+        match self {
+            Self::Suited { value, .. } => Some(*value),
+            Self::Joker => None,
+        }
+    }
+
+    pub fn suit(&self) -> Option<Suit> {
+        match self {
+            Self::Suited { suit, .. } => Some(*suit),
+            Self::Joker => None,
+        }
+    }
+
+    pub fn is_joker(&self) -> bool {
+        *self == Self::Joker
+    }
+
+    /// How strong the card is on its own, ignoring revolutions. Jokers beat everything.
+    pub fn strength(&self) -> u8 {
+        self.value().unwrap_or(Self::TWO + 1)
+    }
+
+    /// Cards nobody may go out on under the foul finish rule: a Joker, a 2, an 8 or the 3 of
+    /// spades.
+    pub fn is_foul_finish(&self) -> bool {
+        match self {
+            Self::Joker => true,
+            Self::Suited { value, suit } => {
+                *value == Self::TWO || *value == 8 || (*value == 3 && *suit == Suit::Spades)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Joker => f.write_str("Joker"),
+            Self::Suited { value, suit } => {
+                let face = match value {
+                    11 => "J".to_string(),
+                    12 => "Q".to_string(),
+                    13 => "K".to_string(),
+                    14 => "A".to_string(),
+                    15 => "2".to_string(),
+                    n => n.to_string(),
+                };
+                write!(f, "{}{}", face, suit.symbol())
+            }
+        }
+    }
+}
+
+impl Deck {
+    /// Every card in the decks, in order.
+    pub fn cards(&self) -> Vec<Card> {
+        let mut cards = vec![];
+        for _ in 0..self.decks {
+            for suit in Suit::all().iter() {
+                for value in Card::LOWEST..=Card::TWO {
+                    cards.push(Card::new(value, *suit));
+                }
+            }
+        }
+        cards.extend((0..self.jokers).map(|_| Card::Joker));
+        cards
+    }
+}
+
+/// A small random number generator (SplitMix64), so games can be shuffled the same way again
+/// from a seed without pulling in a dependency that has to work in the browser.
+#[derive(Debug, Clone)]
+pub struct Shuffler {
+    state: u64,
+}
+
+impl Shuffler {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A number from 0 up to but not including `n`.
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n.max(1) as u64) as usize
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i + 1);
+            items.swap(i, j);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decks_have_every_card() {
+        let cards = Deck::default().cards();
+        assert_eq!(cards.len(), 53);
+        assert_eq!(cards.iter().filter(|c| c.is_joker()).count(), 1);
+        assert_eq!(cards.iter().filter(|c| c.value() == Some(7)).count(), 4);
+        let two_decks = Deck {
+            decks: 2,
+            jokers: 4,
+        };
+        assert_eq!(two_decks.cards().len(), two_decks.size());
+    }
+
+    #[test]
+    fn cards_display_like_a_hand() {
+        assert_eq!(Card::new(10, Suit::Hearts).to_string(), "10♥");
+        assert_eq!(Card::new(Card::TWO, Suit::Spades).to_string(), "2♠");
+        assert_eq!(Card::new(12, Suit::Clubs).to_string(), "Q♣");
+        assert_eq!(Card::Joker.to_string(), "Joker");
+    }
+
+    #[test]
+    fn foul_finish_cards() {
+        assert!(Card::Joker.is_foul_finish());
+        assert!(Card::new(3, Suit::Spades).is_foul_finish());
+        assert!(!Card::new(3, Suit::Hearts).is_foul_finish());
+        assert!(Card::new(8, Suit::Clubs).is_foul_finish());
+        assert!(Card::new(Card::TWO, Suit::Diamonds).is_foul_finish());
+        assert!(!Card::new(Card::ACE, Suit::Diamonds).is_foul_finish());
+    }
+
+    #[test]
+    fn shuffling_is_repeatable() {
+        let mut a = Deck::default().cards();
+        let mut b = a.clone();
+        Shuffler::new(7).shuffle(&mut a);
+        Shuffler::new(7).shuffle(&mut b);
+        assert_eq!(a, b);
+        assert_ne!(a, Deck::default().cards());
+        a.sort();
+        b = Deck::default().cards();
+        b.sort();
+        assert_eq!(a, b);
+    }
+}
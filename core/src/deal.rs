@@ -0,0 +1,247 @@
+use serde::{Deserialize, Serialize};
+
+use crate::exchange::{self, ordinal};
+use crate::player::Player;
+
+/// The cards in play: one or more standard 52 card decks, plus some jokers.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Deck {
+    pub decks: u8,
+    pub jokers: u8,
+}
+
+impl Default for Deck {
+    fn default() -> Self {
+        Self {
+            decks: 1,
+            jokers: 1,
+        }
+    }
+}
+
+impl Deck {
+    pub const MAX_DECKS: u8 = 3;
+
+    pub fn size(&self) -> usize {
+        52 * usize::from(self.decks) + usize::from(self.jokers)
+    }
+
+    /// Jokers are limited to the two that come with each deck.
+    pub fn max_jokers(&self) -> u8 {
+        2 * self.decks
+    }
+
+    pub fn label(&self) -> String {
+        let decks = match self.decks {
+            1 => "1 deck".to_string(),
+            n => format!("{} decks", n),
+        };
+        let jokers = match self.jokers {
+            0 => "no jokers".to_string(),
+            1 => "1 joker".to_string(),
+            n => format!("{} jokers", n),
+        };
+        format!("{} and {} ({} cards)", decks, jokers, self.size())
+    }
+
+    /// Changes the number of decks, ignoring anything that isn't between 1 and `MAX_DECKS`.
+    pub fn with_decks(&self, value: &str) -> Self {
+        match value.trim().parse::<u8>() {
+            Ok(decks) if (1..=Self::MAX_DECKS).contains(&decks) => Self {
+                decks,
+                jokers: self.jokers.min(2 * decks),
+            },
+            _ => *self,
+        }
+    }
+
+    /// Changes the number of jokers, ignoring anything the decks don't have.
+    pub fn with_jokers(&self, value: &str) -> Self {
+        match value.trim().parse::<u8>() {
+            Ok(jokers) if jokers <= self.max_jokers() => Self { jokers, ..*self },
+            _ => *self,
+        }
+    }
+}
+
+/// How the deck splits between the players: everyone gets `cards_each`, and the cards left over
+/// go one each to the players at the bottom of the table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Deal {
+    pub deck_size: usize,
+    pub cards_each: usize,
+    pub leftovers: usize,
+    /// Who receives the leftover cards, first card first. The beggar gets the first one and the
+    /// tycoon never gets one. Empty until everyone has a rank.
+    pub recipients: Vec<usize>,
+}
+
+impl Deal {
+    pub fn new(deck: Deck, players: &[Player]) -> Self {
+        let deck_size = deck.size();
+        let num_players = players.len().max(1);
+        let leftovers = deck_size % num_players;
+        let mut ranked: Vec<(usize, usize)> = exchange::instructions(players)
+            .iter()
+            .filter_map(|i| Some((i.extra_card?, i.pid)))
+            .collect();
+        ranked.sort();
+        Self {
+            deck_size,
+            cards_each: deck_size / num_players,
+            leftovers,
+            recipients: ranked
+                .into_iter()
+                .take(leftovers)
+                .map(|(_, pid)| pid)
+                .collect(),
+        }
+    }
+
+    /// Which leftover card a player gets, counting from 1, if any.
+    pub fn extra_card(&self, pid: usize) -> Option<usize> {
+        self.recipients
+            .iter()
+            .position(|p| *p == pid)
+            .map(|i| i + 1)
+    }
+
+    pub fn cards_for(&self, pid: usize) -> usize {
+        match self.extra_card(pid) {
+            Some(_) => self.cards_each + 1,
+            None => self.cards_each,
+        }
+    }
+
+    pub fn extra_card_text(&self, pid: usize) -> String {
+        match self.extra_card(pid) {
+            Some(n) => format!("receives the {} extra card.", ordinal(n)),
+            None => "receives no extra cards.".into(),
+        }
+    }
+
+    /// The whole deal as a sentence.
+    pub fn describe(&self, players: &[Player]) -> String {
+        let split = format!(
+            "{} cards between {} players is {} each",
+            self.deck_size,
+            players.len(),
+            self.cards_each
+        );
+        if self.leftovers == 0 {
+            return format!("{}, with none left over.", split);
+        }
+        let left = match self.leftovers {
+            1 => "the 1 card left over goes".to_string(),
+            n => format!("the {} cards left over go", n),
+        };
+        if self.recipients.is_empty() {
+            let next = match self.leftovers {
+                1 => "the next player",
+                _ => "the next players, one each,",
+            };
+            return format!("{}, and {} to {} in the deal.", split, left, next);
+        }
+        let names: Vec<&str> = self
+            .recipients
+            .iter()
+            .filter_map(|pid| players.get(*pid))
+            .map(|p| p.name.as_str())
+            .collect();
+        format!("{}, and {} to {}.", split, left, names.join(", then "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::Rank;
+    use crate::test_util::ranked_players;
+
+    #[test]
+    fn leftovers_go_to_the_bottom_of_the_table() {
+        let players = ranked_players(&[Rank::Poor, Rank::Tycoon, Rank::Beggar, Rank::Rich]);
+        let deal = Deal::new(
+            Deck {
+                decks: 1,
+                jokers: 2,
+            },
+            &players,
+        );
+        assert_eq!(deal.cards_each, 13);
+        assert_eq!(deal.recipients, vec![2, 0]);
+        assert_eq!(deal.cards_for(2), 14);
+        assert_eq!(deal.cards_for(3), 13);
+        assert_eq!(deal.extra_card_text(0), "receives the second extra card.");
+        assert_eq!(
+            deal.describe(&players),
+            "54 cards between 4 players is 13 each, and the 2 cards left over go to P2, then P0."
+        );
+    }
+
+    #[test]
+    fn tycoon_never_gets_a_leftover() {
+        let players = ranked_players(&[
+            Rank::Tycoon,
+            Rank::Rich,
+            Rank::Commoner,
+            Rank::Commoner,
+            Rank::Poor,
+            Rank::Beggar,
+            Rank::Commoner,
+        ]);
+        let deal = Deal::new(
+            Deck {
+                decks: 2,
+                jokers: 2,
+            },
+            &players,
+        );
+        // 106 cards leaves one over, for the beggar
+        assert_eq!(deal.cards_each, 15);
+        assert_eq!(deal.leftovers, 1);
+        assert_eq!(deal.recipients, vec![5]);
+
+        let deal = Deal::new(
+            Deck {
+                decks: 1,
+                jokers: 0,
+            },
+            &players,
+        );
+        assert_eq!(deal.recipients, vec![5, 4, 6]);
+        assert_eq!(deal.extra_card(0), None);
+    }
+
+    #[test]
+    fn even_deals_and_unranked_tables() {
+        let players: Vec<Player> = (0..4).map(|i| Player::new(&format!("P{}", i))).collect();
+        let deal = Deal::new(
+            Deck {
+                decks: 1,
+                jokers: 0,
+            },
+            &players,
+        );
+        assert_eq!(
+            deal.describe(&players),
+            "52 cards between 4 players is 13 each, with none left over."
+        );
+        let deal = Deal::new(Deck::default(), &players);
+        assert!(deal.recipients.is_empty());
+        assert_eq!(
+            deal.describe(&players),
+            "53 cards between 4 players is 13 each, and the 1 card left over goes to the next player in the deal."
+        );
+    }
+
+    #[test]
+    fn deck_settings() {
+        let deck = Deck::default().with_decks("2").with_jokers("4");
+        assert_eq!(deck.size(), 108);
+        assert_eq!(deck.with_decks("1").jokers, 2);
+        assert_eq!(deck.with_decks("9"), deck);
+        assert_eq!(deck.with_jokers("5"), deck);
+        assert_eq!(deck.label(), "2 decks and 4 jokers (108 cards)");
+    }
+}
@@ -0,0 +1,634 @@
+//! The card game itself: hands, plays and the pile, so a round can be played out rather than only
+//! scored. Players going out are reported to a `Game`, which ranks and scores them as usual.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::cards::{Card, Shuffler, Suit};
+use crate::exchange::Exchange;
+use crate::game::Game;
+use crate::player::Rank;
+
+/// The shape of a play. Every play on the pile has to have the same shape as the one that
+/// started it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Combo {
+    /// Cards that all have the same value: a single, a pair, a triple and so on.
+    Set(usize),
+    /// Three or more cards of one suit in a row.
+    Sequence(usize),
+}
+
+/// Cards played together.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Play {
+    /// Weakest first.
+    pub cards: Vec<Card>,
+    pub combo: Combo,
+    /// The strength of the weakest card.
+    pub strength: u8,
+}
+
+impl Play {
+    /// The play made by these cards, or `None` if they don't go together. Jokers can only be
+    /// played as singles.
+    pub fn new(cards: &[Card]) -> Option<Self> {
+        let mut cards = cards.to_vec();
+        cards.sort_by_key(|c| (c.strength(), c.suit()));
+        let strength = cards.first()?.strength();
+        if cards.len() == 1 {
+            return Some(Self {
+                cards,
+                combo: Combo::Set(1),
+                strength,
+            });
+        }
+        let values: Vec<u8> = cards.iter().map(Card::value).collect::<Option<_>>()?;
+        let combo = if values.iter().all(|v| *v == values[0]) {
+            Combo::Set(cards.len())
+        } else if cards.len() >= 3
+            && cards.iter().all(|c| c.suit() == cards[0].suit())
+            && values.windows(2).all(|pair| pair[1] == pair[0] + 1)
+        {
+            Combo::Sequence(cards.len())
+        } else {
+            return None;
+        };
+        Some(Self {
+            cards,
+            combo,
+            strength,
+        })
+    }
+
+    /// Four or more of a kind starts a revolution.
+    pub fn is_revolution(&self) -> bool {
+        matches!(self.combo, Combo::Set(n) if n >= 4)
+    }
+
+    fn is_joker(&self) -> bool {
+        self.cards.len() == 1 && self.cards[0].is_joker()
+    }
+
+    /// Whether this can go on top of `other`: the same shape, and stronger, or weaker during a
+    /// revolution.
+    pub fn beats(&self, other: &Play, reversed: bool) -> bool {
+        if self.combo != other.combo {
+            return false;
+        }
+        // A joker is the strongest single whichever way round the cards are
+        if self.is_joker() || other.is_joker() {
+            return self.is_joker() && !other.is_joker();
+        }
+        if reversed {
+            self.strength < other.strength
+        } else {
+            self.strength > other.strength
+        }
+    }
+}
+
+impl fmt::Display for Play {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let cards: Vec<String> = self.cards.iter().map(|c| c.to_string()).collect();
+        f.write_str(&cards.join(" "))
+    }
+}
+
+/// Why a play or pass wasn't allowed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlayError {
+    /// The card exchange hasn't finished yet.
+    RoundNotStarted,
+    RoundOver,
+    NotYourTurn,
+    NotInHand,
+    NotACombination,
+    /// The play isn't the same shape as the one on the pile.
+    WrongShape,
+    TooWeak,
+    /// The player starting a new pile can't pass.
+    MustLead,
+    /// An exchange was given the wrong number of cards.
+    WrongCount(usize),
+}
+
+impl fmt::Display for PlayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::RoundNotStarted => f.write_str("the cards haven't been exchanged yet"),
+            Self::RoundOver => f.write_str("the round is over"),
+            Self::NotYourTurn => f.write_str("it isn't your turn"),
+            Self::NotInHand => f.write_str("those cards aren't in your hand"),
+            Self::NotACombination => f.write_str("those cards can't be played together"),
+            Self::WrongShape => f.write_str("that doesn't match what's on the pile"),
+            Self::TooWeak => f.write_str("that doesn't beat what's on the pile"),
+            Self::MustLead => f.write_str("you're starting the pile, so you have to play"),
+            Self::WrongCount(n) => write!(f, "exactly {} cards have to be passed", n),
+        }
+    }
+}
+
+impl std::error::Error for PlayError {}
+
+/// The cards in play for one round, by seat.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Table {
+    /// Each player's cards, weakest first.
+    pub hands: Vec<Vec<Card>>,
+    /// The plays since the pile was last cleared, and who made them, latest last.
+    pub pile: Vec<(usize, Play)>,
+    /// Whose turn it is.
+    pub turn: usize,
+    /// Players who have passed since the pile was last cleared.
+    pub passed: Vec<bool>,
+    /// Players who have a rank for the round, and so take no more turns.
+    pub out: Vec<bool>,
+    /// Whether a revolution has reversed the strength of the cards.
+    pub reversed: bool,
+}
+
+fn sort_hand(hand: &mut [Card]) {
+    hand.sort_by_key(|c| (c.strength(), c.suit()));
+}
+
+impl Table {
+    /// A table with the hands already dealt, `turn` to lead.
+    pub fn new(mut hands: Vec<Vec<Card>>, turn: usize) -> Self {
+        for hand in hands.iter_mut() {
+            sort_hand(hand);
+        }
+        let num_players = hands.len();
+        Self {
+            hands,
+            pile: vec![],
+            turn,
+            passed: vec![false; num_players],
+            out: vec![false; num_players],
+            reversed: false,
+        }
+    }
+
+    /// Shuffles the game's deck and deals it out as `Game::deal` says. The beggar from the last
+    /// round leads, or in the first round whoever has the 3 of diamonds.
+    pub fn deal(game: &Game, shuffler: &mut Shuffler) -> Self {
+        let num_players = game.players.len();
+        let deal = game.deal();
+        let mut cards = game.deck.cards();
+        shuffler.shuffle(&mut cards);
+        let mut cards = cards.into_iter();
+        let mut hands: Vec<Vec<Card>> = (0..num_players)
+            .map(|_| cards.by_ref().take(deal.cards_each).collect())
+            .collect();
+        // Before anyone has a rank the leftovers carry on around the table from the first seat
+        let recipients = if deal.recipients.is_empty() {
+            (0..deal.leftovers).collect()
+        } else {
+            deal.recipients
+        };
+        for (pid, card) in recipients.into_iter().zip(cards) {
+            hands[pid].push(card);
+        }
+        let three_of_diamonds = Card::new(3, Suit::Diamonds);
+        let leader = game
+            .players
+            .iter()
+            .position(|p| p.rank == Some(Rank::Beggar))
+            .or_else(|| hands.iter().position(|h| h.contains(&three_of_diamonds)))
+            .unwrap_or(0);
+        let mut table = Self::new(hands, leader);
+        table.sync_out(game);
+        table
+    }
+
+    /// The play on top of the pile, if there is one.
+    pub fn top(&self) -> Option<&Play> {
+        self.pile.last().map(|(_, play)| play)
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.out.iter().all(|out| *out)
+    }
+
+    /// A player's `count` strongest cards, which is what the lower ranked side of an exchange
+    /// has to hand over.
+    pub fn highest_cards(&self, pid: usize, count: usize) -> Vec<Card> {
+        let hand = &self.hands[pid];
+        hand[hand.len().saturating_sub(count)..].to_vec()
+    }
+
+    /// Swaps cards for an exchange: the higher ranked player hands over `chosen`, and gets the
+    /// other player's highest cards back, which are returned.
+    pub fn exchange(
+        &mut self,
+        exchange: &Exchange,
+        chosen: &[Card],
+    ) -> Result<Vec<Card>, PlayError> {
+        if chosen.len() != exchange.count {
+            return Err(PlayError::WrongCount(exchange.count));
+        }
+        if !self.holds(exchange.upper, chosen) {
+            return Err(PlayError::NotInHand);
+        }
+        let highest = self.highest_cards(exchange.lower, exchange.count);
+        self.take(exchange.upper, chosen);
+        self.take(exchange.lower, &highest);
+        self.hands[exchange.lower].extend_from_slice(chosen);
+        self.hands[exchange.upper].extend_from_slice(&highest);
+        sort_hand(&mut self.hands[exchange.lower]);
+        sort_hand(&mut self.hands[exchange.upper]);
+        Ok(highest)
+    }
+
+    /// Every play a player could make now, weakest first within each shape.
+    pub fn legal_plays(&self, pid: usize) -> Vec<Play> {
+        let hand = match self.hands.get(pid) {
+            Some(hand) => hand,
+            None => return vec![],
+        };
+        let mut plays = vec![];
+        let mut by_value: BTreeMap<u8, Vec<Card>> = BTreeMap::new();
+        let mut by_suit: BTreeMap<Suit, BTreeMap<u8, Card>> = BTreeMap::new();
+        for card in hand.iter() {
+            match (card.value(), card.suit()) {
+                (Some(value), Some(suit)) => {
+                    by_value.entry(value).or_default().push(*card);
+                    by_suit.entry(suit).or_default().insert(value, *card);
+                }
+                _ => plays.extend(Play::new(&[*card])),
+            }
+        }
+        for cards in by_value.values() {
+            for count in 1..=cards.len() {
+                plays.extend(Play::new(&cards[..count]));
+            }
+        }
+        for cards in by_suit.values() {
+            let cards: Vec<(u8, Card)> = cards.iter().map(|(v, c)| (*v, *c)).collect();
+            for start in 0..cards.len() {
+                let mut end = start + 1;
+                while end < cards.len() && cards[end].0 == cards[end - 1].0 + 1 {
+                    end += 1;
+                    if end - start >= 3 {
+                        let run: Vec<Card> = cards[start..end].iter().map(|(_, c)| *c).collect();
+                        plays.extend(Play::new(&run));
+                    }
+                }
+            }
+        }
+        plays.dedup();
+        match self.top() {
+            Some(top) => plays
+                .into_iter()
+                .filter(|play| play.beats(top, self.reversed))
+                .collect(),
+            None => plays,
+        }
+    }
+
+    /// Plays cards for a player. When their hand runs out they go out in `game`, which may send
+    /// a bankrupt tycoon out too, and four of a kind is recorded as a revolution.
+    pub fn play(&mut self, game: &mut Game, pid: usize, cards: &[Card]) -> Result<(), PlayError> {
+        self.check_turn(game, pid)?;
+        let play = Play::new(cards).ok_or(PlayError::NotACombination)?;
+        if !self.holds(pid, &play.cards) {
+            return Err(PlayError::NotInHand);
+        }
+        if let Some(top) = self.top() {
+            if play.combo != top.combo {
+                return Err(PlayError::WrongShape);
+            }
+            if !play.beats(top, self.reversed) {
+                return Err(PlayError::TooWeak);
+            }
+        }
+        self.take(pid, &play.cards);
+        if play.is_revolution() {
+            self.reversed = !self.reversed;
+            game.handle_revolution(pid);
+        }
+        if self.hands[pid].is_empty() {
+            if game.foul_finish && play.cards.iter().any(Card::is_foul_finish) {
+                game.handle_foul_finish(pid);
+            } else {
+                game.handle_go_out(pid);
+            }
+            self.sync_out(game);
+        }
+        self.pile.push((pid, play));
+        self.next_turn(pid);
+        Ok(())
+    }
+
+    /// Passes for a player, who then sits out until the pile is cleared.
+    pub fn pass(&mut self, game: &Game, pid: usize) -> Result<(), PlayError> {
+        self.check_turn(game, pid)?;
+        if self.pile.is_empty() {
+            return Err(PlayError::MustLead);
+        }
+        self.passed[pid] = true;
+        self.next_turn(pid);
+        Ok(())
+    }
+
+    fn check_turn(&self, game: &Game, pid: usize) -> Result<(), PlayError> {
+        if game.exchanging {
+            Err(PlayError::RoundNotStarted)
+        } else if self.is_over() {
+            Err(PlayError::RoundOver)
+        } else if pid != self.turn {
+            Err(PlayError::NotYourTurn)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn holds(&self, pid: usize, cards: &[Card]) -> bool {
+        let mut hand = self.hands[pid].clone();
+        cards
+            .iter()
+            .all(|card| match hand.iter().position(|c| c == card) {
+                Some(i) => {
+                    hand.remove(i);
+                    true
+                }
+                None => false,
+            })
+    }
+
+    fn take(&mut self, pid: usize, cards: &[Card]) {
+        let hand = &mut self.hands[pid];
+        for card in cards.iter() {
+            if let Some(i) = hand.iter().position(|c| c == card) {
+                hand.remove(i);
+            }
+        }
+    }
+
+    /// Marks everyone the game has ranked as out, including a bankrupt tycoon.
+    fn sync_out(&mut self, game: &Game) {
+        for (pid, out) in self.out.iter_mut().enumerate() {
+            *out = game.players_out.contains_key(&pid);
+        }
+    }
+
+    /// Moves on to the next player still in the pile. Once everyone else has passed, the pile is
+    /// cleared and whoever played last leads, or the next player along if they've gone out.
+    fn next_turn(&mut self, from: usize) {
+        let num_players = self.hands.len();
+        let last = self.pile.last().map(|(pid, _)| *pid);
+        let waiting = (1..=num_players)
+            .map(|step| (from + step) % num_players)
+            .find(|pid| !self.out[*pid] && !self.passed[*pid] && Some(*pid) != last);
+        match waiting {
+            Some(pid) => self.turn = pid,
+            None => {
+                let leader = last.unwrap_or(from);
+                self.pile.clear();
+                self.passed = vec![false; num_players];
+                self.turn = (0..num_players)
+                    .map(|step| (leader + step) % num_players)
+                    .find(|pid| !self.out[*pid])
+                    .unwrap_or(leader);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::game_with;
+
+    fn card(value: u8, suit: Suit) -> Card {
+        Card::new(value, suit)
+    }
+
+    #[test]
+    fn plays_have_a_shape() {
+        let pair = Play::new(&[card(9, Suit::Hearts), card(9, Suit::Clubs)]).unwrap();
+        assert_eq!(pair.combo, Combo::Set(2));
+        assert_eq!(pair.strength, 9);
+        let run = Play::new(&[
+            card(6, Suit::Spades),
+            card(4, Suit::Spades),
+            card(5, Suit::Spades),
+        ])
+        .unwrap();
+        assert_eq!(run.combo, Combo::Sequence(3));
+        assert_eq!(run.to_string(), "4♠ 5♠ 6♠");
+        assert_eq!(
+            Play::new(&[card(4, Suit::Spades), card(5, Suit::Hearts)]),
+            None
+        );
+        assert_eq!(
+            Play::new(&[
+                card(4, Suit::Spades),
+                card(5, Suit::Spades),
+                card(7, Suit::Spades)
+            ]),
+            None
+        );
+        assert_eq!(Play::new(&[Card::Joker, Card::Joker]), None);
+        assert_eq!(Play::new(&[]), None);
+    }
+
+    #[test]
+    fn stronger_plays_beat_weaker_ones() {
+        let three = Play::new(&[card(3, Suit::Clubs)]).unwrap();
+        let two = Play::new(&[card(Card::TWO, Suit::Clubs)]).unwrap();
+        let joker = Play::new(&[Card::Joker]).unwrap();
+        let pair = Play::new(&[card(4, Suit::Clubs), card(4, Suit::Hearts)]).unwrap();
+        assert!(two.beats(&three, false));
+        assert!(!three.beats(&two, false));
+        assert!(three.beats(&two, true));
+        assert!(joker.beats(&two, false));
+        assert!(joker.beats(&three, true));
+        assert!(!two.beats(&joker, true));
+        assert!(!pair.beats(&three, false));
+    }
+
+    #[test]
+    fn passing_clears_the_pile() {
+        let mut game = game_with(3);
+        let mut table = Table::new(
+            vec![
+                vec![card(3, Suit::Clubs), card(10, Suit::Clubs)],
+                vec![card(5, Suit::Hearts), card(6, Suit::Hearts)],
+                vec![card(4, Suit::Spades), card(7, Suit::Spades)],
+            ],
+            0,
+        );
+        assert_eq!(table.pass(&game, 0), Err(PlayError::MustLead));
+        table.play(&mut game, 0, &[card(3, Suit::Clubs)]).unwrap();
+        assert_eq!(
+            table.play(&mut game, 2, &[card(4, Suit::Spades)]),
+            Err(PlayError::NotYourTurn)
+        );
+        table.play(&mut game, 1, &[card(5, Suit::Hearts)]).unwrap();
+        assert_eq!(
+            table.play(&mut game, 2, &[card(4, Suit::Spades)]),
+            Err(PlayError::TooWeak)
+        );
+        table.pass(&game, 2).unwrap();
+        table.pass(&game, 0).unwrap();
+        // Everyone else passed, so the last player to play starts a new pile
+        assert!(table.pile.is_empty());
+        assert_eq!(table.turn, 1);
+        assert_eq!(table.legal_plays(1).len(), 1);
+    }
+
+    #[test]
+    fn emptying_a_hand_goes_out() {
+        let mut game = game_with(3);
+        let mut table = Table::new(
+            vec![
+                vec![card(Card::ACE, Suit::Clubs)],
+                vec![card(5, Suit::Hearts), card(6, Suit::Hearts)],
+                vec![card(4, Suit::Spades), card(7, Suit::Spades)],
+            ],
+            0,
+        );
+        table
+            .play(&mut game, 0, &[card(Card::ACE, Suit::Clubs)])
+            .unwrap();
+        assert_eq!(game.players_out[&0], Rank::Tycoon);
+        // Nobody can beat the ace, so the pile clears and the next player along leads
+        table.pass(&game, 1).unwrap();
+        table.pass(&game, 2).unwrap();
+        assert_eq!(table.turn, 1);
+        table.play(&mut game, 1, &[card(5, Suit::Hearts)]).unwrap();
+        table.play(&mut game, 2, &[card(7, Suit::Spades)]).unwrap();
+        table.pass(&game, 1).unwrap();
+        table.play(&mut game, 2, &[card(4, Suit::Spades)]).unwrap();
+        assert!(table.is_over());
+        assert!(game.is_round_complete());
+        assert_eq!(game.players_out[&2], Rank::Commoner);
+        assert_eq!(game.players_out[&1], Rank::Beggar);
+        assert_eq!(
+            table.play(&mut game, 1, &[card(6, Suit::Hearts)]),
+            Err(PlayError::RoundOver)
+        );
+    }
+
+    #[test]
+    fn four_of_a_kind_starts_a_revolution() {
+        let mut game = game_with(3);
+        let fours: Vec<Card> = Suit::all().iter().map(|s| card(4, *s)).collect();
+        let mut hand = fours.clone();
+        hand.push(card(3, Suit::Hearts));
+        let mut table = Table::new(
+            vec![
+                hand,
+                vec![card(5, Suit::Hearts), card(6, Suit::Hearts)],
+                vec![card(Card::TWO, Suit::Spades), card(7, Suit::Spades)],
+            ],
+            0,
+        );
+        table.play(&mut game, 0, &fours).unwrap();
+        assert!(table.reversed);
+        assert!(game.is_reversed());
+        table.pass(&game, 1).unwrap();
+        table.pass(&game, 2).unwrap();
+        table.play(&mut game, 0, &[card(3, Suit::Hearts)]).unwrap();
+        // With the cards reversed nothing beats a 3
+        assert!(table.legal_plays(1).is_empty());
+        assert!(table.legal_plays(2).is_empty());
+    }
+
+    #[test]
+    fn going_out_on_a_two_is_a_foul() {
+        let mut game = game_with(3);
+        game.foul_finish = true;
+        let mut table = Table::new(
+            vec![
+                vec![card(Card::TWO, Suit::Clubs)],
+                vec![card(5, Suit::Hearts)],
+                vec![card(4, Suit::Spades), card(7, Suit::Spades)],
+            ],
+            0,
+        );
+        table
+            .play(&mut game, 0, &[card(Card::TWO, Suit::Clubs)])
+            .unwrap();
+        assert_eq!(game.players_out[&0], Rank::Beggar);
+    }
+
+    #[test]
+    fn legal_plays_include_every_shape() {
+        let table = Table::new(
+            vec![vec![
+                card(5, Suit::Hearts),
+                card(6, Suit::Hearts),
+                card(7, Suit::Hearts),
+                card(8, Suit::Hearts),
+                card(7, Suit::Clubs),
+                Card::Joker,
+            ]],
+            0,
+        );
+        let shapes: Vec<Combo> = table.legal_plays(0).iter().map(|p| p.combo).collect();
+        // One play for each value, as it doesn't matter which of the 7s goes
+        assert_eq!(shapes.iter().filter(|c| **c == Combo::Set(1)).count(), 5);
+        assert_eq!(shapes.iter().filter(|c| **c == Combo::Set(2)).count(), 1);
+        assert_eq!(
+            shapes.iter().filter(|c| **c == Combo::Sequence(3)).count(),
+            2
+        );
+        assert_eq!(
+            shapes.iter().filter(|c| **c == Combo::Sequence(4)).count(),
+            1
+        );
+    }
+
+    #[test]
+    fn dealing_uses_the_whole_deck() {
+        let game = game_with(4);
+        let table = Table::deal(&game, &mut Shuffler::new(1));
+        let sizes: Vec<usize> = table.hands.iter().map(|h| h.len()).collect();
+        assert_eq!(sizes, vec![14, 13, 13, 13]);
+        assert!(table.hands[table.turn].contains(&card(3, Suit::Diamonds)));
+    }
+
+    #[test]
+    fn exchanges_swap_the_cards() {
+        let mut game = game_with(3);
+        game.handle_go_out(0);
+        game.handle_go_out(1);
+        game.handle_end_round();
+        let exchange = game.exchanges[0].clone();
+        let mut table = Table::new(
+            vec![
+                vec![
+                    card(3, Suit::Clubs),
+                    card(4, Suit::Clubs),
+                    card(9, Suit::Clubs),
+                ],
+                vec![card(5, Suit::Hearts)],
+                vec![
+                    card(5, Suit::Spades),
+                    Card::Joker,
+                    card(Card::ACE, Suit::Spades),
+                ],
+            ],
+            2,
+        );
+        assert_eq!(
+            table.exchange(&exchange, &[card(3, Suit::Clubs)]),
+            Err(PlayError::WrongCount(2))
+        );
+        let given = table
+            .exchange(&exchange, &[card(3, Suit::Clubs), card(4, Suit::Clubs)])
+            .unwrap();
+        assert_eq!(given, vec![card(Card::ACE, Suit::Spades), Card::Joker]);
+        assert_eq!(table.hands[0].len(), 3);
+        assert!(table.hands[0].contains(&Card::Joker));
+        assert!(table.hands[2].contains(&card(3, Suit::Clubs)));
+        // Nobody plays until the exchange is confirmed in the game
+        assert_eq!(
+            table.play(&mut game, 2, &[card(3, Suit::Clubs)]),
+            Err(PlayError::RoundNotStarted)
+        );
+    }
+}
@@ -0,0 +1,271 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+
+use crate::deal::Deal;
+use crate::player::{Player, Rank};
+
+/// Cards one player has to hand to another before the next round starts.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pass {
+    pub count: usize,
+    /// Whether they have to give up their best cards rather than cards of their choice.
+    pub highest: bool,
+    pub to: usize,
+}
+
+impl Pass {
+    pub fn cards_text(&self) -> &'static str {
+        match (self.count, self.highest) {
+            (1, false) => "one card",
+            (_, false) => "two cards",
+            (1, true) => "highest card",
+            (_, true) => "two highest cards",
+        }
+    }
+}
+
+/// What a player does between rounds: the cards they pass, and which of the leftover cards from
+/// the deal they receive.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Instruction {
+    pub pid: usize,
+    pub rank: Rank,
+    pub pass: Option<Pass>,
+    /// Leftover cards are handed out from the bottom of the table up, so the beggar gets the
+    /// first one. The tycoon never gets one.
+    pub extra_card: Option<usize>,
+}
+
+impl Instruction {
+    /// The instruction as a sentence, for places without any styling.
+    pub fn describe(&self, players: &[Player]) -> String {
+        self.describe_with_extra(players, &self.extra_card_text())
+    }
+
+    /// The instruction as a sentence, saying exactly which extra card the player gets in `deal`.
+    pub fn describe_for_deal(&self, players: &[Player], deal: &Deal) -> String {
+        self.describe_with_extra(players, &deal.extra_card_text(self.pid))
+    }
+
+    fn describe_with_extra(&self, players: &[Player], extra_card_text: &str) -> String {
+        let name = |pid: usize| match players.get(pid) {
+            Some(p) => p.name.as_str(),
+            None => "",
+        };
+        let passes = match &self.pass {
+            Some(pass) if pass.highest => {
+                format!("passes their {} to {}", pass.cards_text(), name(pass.to))
+            }
+            Some(pass) => format!(
+                "passes {} of their choice to {}",
+                pass.cards_text(),
+                name(pass.to)
+            ),
+            None => "exchanges no cards".into(),
+        };
+        format!("{}: {}, and {}", name(self.pid), passes, extra_card_text)
+    }
+
+    pub fn extra_card_text(&self) -> String {
+        match self.extra_card {
+            Some(n) => format!("receives the {} extra card (if applicable)", ordinal(n)),
+            None => "receives no extra cards.".into(),
+        }
+    }
+}
+
+pub fn ordinal(n: usize) -> &'static str {
+    match n {
+        1 => "first",
+        2 => "second",
+        3 => "third",
+        4 => "fourth",
+        5 => "fifth",
+        6 => "sixth",
+        _ => "seventh",
+    }
+}
+
+/// Who passes what to whom, based on the ranks from the last round, from the tycoon down to the
+/// beggar. Empty until everyone has a rank.
+pub fn instructions(players: &[Player]) -> Vec<Instruction> {
+    let mut ranked: Vec<(usize, Rank)> = vec![];
+    for (pid, player) in players.iter().enumerate() {
+        match player.rank {
+            Some(rank) => ranked.push((pid, rank)),
+            None => return vec![],
+        }
+    }
+    // Best rank first, commoners in seat order
+    ranked.sort_by_key(|(pid, rank)| (Reverse(*rank), *pid));
+    let find = |rank: Rank| ranked.iter().find(|(_, r)| *r == rank).map(|(pid, _)| *pid);
+    let pass_to =
+        |rank: Rank, count: usize, highest: bool| find(rank).map(|to| Pass { count, highest, to });
+
+    let num_players = ranked.len();
+    ranked
+        .iter()
+        .enumerate()
+        .map(|(i, (pid, rank))| Instruction {
+            pid: *pid,
+            rank: *rank,
+            pass: match rank {
+                Rank::Tycoon => pass_to(Rank::Beggar, 2, false),
+                Rank::Rich => pass_to(Rank::Poor, 1, false),
+                Rank::Commoner => None,
+                Rank::Poor => pass_to(Rank::Rich, 1, true),
+                Rank::Beggar => pass_to(Rank::Tycoon, 2, true),
+            },
+            extra_card: if i == 0 { None } else { Some(num_players - i) },
+        })
+        .collect()
+}
+
+/// Two players swapping cards before a round: the Tycoon and the Beggar, or the Rich and the
+/// Poor. Each hands the other `count` cards.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Exchange {
+    /// The higher ranked player, who passes cards of their choice.
+    pub upper: usize,
+    /// The lower ranked player, who passes their highest cards.
+    pub lower: usize,
+    pub count: usize,
+    /// Whether both players have confirmed they've passed their cards.
+    pub done: bool,
+    /// The cards each player passed, if anyone wrote them down.
+    #[serde(default)]
+    pub upper_cards: String,
+    #[serde(default)]
+    pub lower_cards: String,
+}
+
+impl Exchange {
+    pub fn describe(&self, players: &[Player]) -> String {
+        let name = |pid: usize| match players.get(pid) {
+            Some(p) => p.name.as_str(),
+            None => "",
+        };
+        let cards = match self.count {
+            1 => "one card",
+            _ => "two cards",
+        };
+        format!(
+            "{} and {} swap {}",
+            name(self.upper),
+            name(self.lower),
+            cards
+        )
+    }
+}
+
+/// The exchanges to confirm before the next round, Tycoon and Beggar first. Empty until everyone
+/// has a rank.
+pub fn checklist(players: &[Player]) -> Vec<Exchange> {
+    instructions(players)
+        .into_iter()
+        .filter_map(|i| match i.pass {
+            // Each swap is listed once, from the side passing cards of their choice
+            Some(pass) if !pass.highest => Some(Exchange {
+                upper: i.pid,
+                lower: pass.to,
+                count: pass.count,
+                done: false,
+                upper_cards: String::new(),
+                lower_cards: String::new(),
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::ranked_players;
+
+    #[test]
+    fn four_player_exchange() {
+        let players = ranked_players(&[Rank::Poor, Rank::Tycoon, Rank::Beggar, Rank::Rich]);
+        let lines: Vec<String> = instructions(&players)
+            .iter()
+            .map(|i| i.describe(&players))
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                "P1: passes two cards of their choice to P2, and receives no extra cards.",
+                "P3: passes one card of their choice to P0, and receives the third extra card (if applicable)",
+                "P0: passes their highest card to P3, and receives the second extra card (if applicable)",
+                "P2: passes their two highest cards to P1, and receives the first extra card (if applicable)",
+            ]
+        );
+    }
+
+    #[test]
+    fn commoners_keep_their_cards() {
+        let players = ranked_players(&[
+            Rank::Tycoon,
+            Rank::Commoner,
+            Rank::Rich,
+            Rank::Commoner,
+            Rank::Beggar,
+            Rank::Poor,
+        ]);
+        let instructions = instructions(&players);
+        let order: Vec<(usize, Option<usize>)> =
+            instructions.iter().map(|i| (i.pid, i.extra_card)).collect();
+        assert_eq!(
+            order,
+            vec![
+                (0, None),
+                (2, Some(5)),
+                (1, Some(4)),
+                (3, Some(3)),
+                (5, Some(2)),
+                (4, Some(1))
+            ]
+        );
+        assert_eq!(instructions[2].pass, None);
+        assert_eq!(instructions[3].pass, None);
+    }
+
+    #[test]
+    fn three_players_only_swap_tycoon_and_beggar() {
+        let players = ranked_players(&[Rank::Beggar, Rank::Commoner, Rank::Tycoon]);
+        let instructions = instructions(&players);
+        assert_eq!(instructions.len(), 3);
+        assert_eq!(
+            instructions[0].pass,
+            Some(Pass {
+                count: 2,
+                highest: false,
+                to: 0
+            })
+        );
+        assert_eq!(instructions[1].pass, None);
+        assert_eq!(instructions[1].extra_card, Some(2));
+    }
+
+    #[test]
+    fn nothing_to_do_before_anyone_has_a_rank() {
+        let players: Vec<Player> = (0..4).map(|_| Player::new("P")).collect();
+        assert!(instructions(&players).is_empty());
+        assert!(checklist(&players).is_empty());
+    }
+
+    #[test]
+    fn checklist_pairs_up_the_swaps() {
+        let players = ranked_players(&[Rank::Poor, Rank::Tycoon, Rank::Beggar, Rank::Rich]);
+        let pairs: Vec<String> = checklist(&players)
+            .iter()
+            .map(|e| e.describe(&players))
+            .collect();
+        assert_eq!(
+            pairs,
+            vec!["P1 and P2 swap two cards", "P3 and P0 swap one card"]
+        );
+
+        let players = ranked_players(&[Rank::Beggar, Rank::Commoner, Rank::Tycoon]);
+        assert_eq!(checklist(&players).len(), 1);
+    }
+}
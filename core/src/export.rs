@@ -0,0 +1,536 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::bots::Difficulty;
+use crate::deal::Deck;
+use crate::game::{same_name, Game, RoundRecord};
+use crate::player::{Player, Rank, MAX_PLAYERS, MIN_PLAYERS};
+use crate::rules::{Bankruptcy, GameLength, ScoringTable, TieBreak};
+
+/// A finished (or abandoned) game laid out round by round, for keeping records outside the app.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GameExport {
+    pub game_length: GameLength,
+    pub scoring: ScoringTable,
+    #[serde(default)]
+    pub tie_break: TieBreak,
+    #[serde(default)]
+    pub bankruptcy: Bankruptcy,
+    #[serde(default)]
+    pub revolution_bonus: i32,
+    #[serde(default)]
+    pub foul_finish: bool,
+    #[serde(default)]
+    pub deck: Deck,
+    pub rounds_played: u16,
+    /// Rounds played when someone last joined or left. Players who joined late have no ranks for
+    /// the rounds before they sat down.
+    #[serde(default)]
+    pub roster_changed: u16,
+    /// In seat order.
+    pub players: Vec<PlayerExport>,
+    /// The revolutions in each round. Files from before revolutions were recorded have none.
+    #[serde(default)]
+    pub rounds: Vec<RoundRecord>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayerExport {
+    pub name: String,
+    /// The rank earned in each round the player was at the table for, up to the last round.
+    pub ranks: Vec<Rank>,
+    /// The points each of those ranks was worth.
+    pub points: Vec<i32>,
+    pub total: i64,
+    /// Set for seats the computer played.
+    #[serde(default)]
+    pub bot: Option<Difficulty>,
+}
+
+impl GameExport {
+    pub fn new(game: &Game) -> Self {
+        let players = game
+            .players
+            .iter()
+            .map(|player| {
+                let results = player.round_results(&game.scoring);
+                PlayerExport {
+                    name: player.name.clone(),
+                    ranks: results.iter().map(|r| r.rank).collect(),
+                    points: results.iter().map(|r| r.points).collect(),
+                    total: player.score,
+                    bot: player.bot,
+                }
+            })
+            .collect();
+        Self {
+            game_length: game.game_length,
+            scoring: game.scoring,
+            tie_break: game.tie_break,
+            bankruptcy: game.bankruptcy,
+            revolution_bonus: game.revolution_bonus,
+            foul_finish: game.foul_finish,
+            deck: game.deck,
+            rounds_played: game.round - 1,
+            roster_changed: game.roster_changed,
+            players,
+            rounds: game.round_records.clone(),
+        }
+    }
+
+    /// One row per player with the rank and points for every round, ready for a spreadsheet.
+    pub fn to_csv(&self) -> String {
+        let mut header = vec!["Player".to_string()];
+        for round in 1..=self.rounds_played {
+            header.push(format!("Round {} Rank", round));
+            header.push(format!("Round {} Points", round));
+        }
+        header.push("Total".into());
+
+        let mut lines = vec![header.join(",")];
+        for player in self.players.iter() {
+            let mut row = vec![csv_field(&player.name)];
+            // Leave the rounds before a late joiner sat down blank
+            for _ in player.ranks.len()..usize::from(self.rounds_played) {
+                row.push(String::new());
+                row.push(String::new());
+            }
+            for (rank, points) in player.ranks.iter().zip(player.points.iter()) {
+                row.push(rank.to_string());
+                row.push(points.to_string());
+            }
+            row.push(player.total.to_string());
+            lines.push(row.join(","));
+        }
+        lines.join("\n") + "\n"
+    }
+
+    /// Rebuilds the game this was exported from, after checking that it's a game the rules could
+    /// actually have produced.
+    pub fn into_game(self) -> Result<Game, ImportError> {
+        let num_players = self.players.len();
+        if !(MIN_PLAYERS..=MAX_PLAYERS).contains(&num_players) {
+            return Err(ImportError::new(format!(
+                "Tycoon needs between {} and {} players, but the file has {}",
+                MIN_PLAYERS, MAX_PLAYERS, num_players
+            )));
+        }
+        let rounds_played = usize::from(self.rounds_played);
+        let round = match self.rounds_played.checked_add(1) {
+            Some(round) => round,
+            None => return Err(ImportError::new("the game has too many rounds to continue")),
+        };
+        let round_records = if self.rounds.is_empty() {
+            vec![RoundRecord::default(); rounds_played]
+        } else {
+            self.rounds.clone()
+        };
+        if round_records.len() != rounds_played {
+            return Err(ImportError::new(format!(
+                "the file describes {} rounds, but {} were played",
+                round_records.len(),
+                self.rounds_played
+            )));
+        }
+        for (round, record) in round_records.iter().enumerate() {
+            let pids = record.revolutions.iter().map(|r| r.pid);
+            let exchanges = record.exchanges.iter().flat_map(|e| vec![e.upper, e.lower]);
+            if pids
+                .chain(exchanges)
+                .chain(record.fouls.iter().copied())
+                .chain(record.dropped_out)
+                .any(|pid| pid >= num_players)
+            {
+                return Err(ImportError::new(format!(
+                    "round {} mentions a player who isn't at the table",
+                    round + 1
+                )));
+            }
+        }
+        if !(1..=Deck::MAX_DECKS).contains(&self.deck.decks)
+            || self.deck.jokers > self.deck.max_jokers()
+        {
+            return Err(ImportError::new(format!(
+                "a game can't be dealt from {}",
+                self.deck.label()
+            )));
+        }
+        for i in 0..num_players {
+            self.check_player(i, &round_records, &self.players[..i])?;
+        }
+        if self.roster_changed > self.rounds_played {
+            return Err(ImportError::new(format!(
+                "the players changed after round {}, but only {} rounds were played",
+                self.roster_changed, self.rounds_played
+            )));
+        }
+        // Only rounds since the last change were played by this table
+        for round in usize::from(self.roster_changed)..rounds_played {
+            check_round(
+                round,
+                self.players
+                    .iter()
+                    .map(|p| p.ranks[p.ranks.len() + round - rounds_played]),
+            )?;
+        }
+
+        let players: Vec<Player> = self
+            .players
+            .into_iter()
+            .map(|mut export| {
+                let mut player = Player::new(&export.name);
+                player.rank = export.ranks.pop();
+                player.past_ranks = export.ranks;
+                player.round_points = export.points;
+                player.score = export.total;
+                player.bot = export.bot;
+                player
+            })
+            .collect();
+        let mut game = Game {
+            players,
+            round,
+            tie_break: self.tie_break,
+            bankruptcy: self.bankruptcy,
+            revolution_bonus: self.revolution_bonus,
+            foul_finish: self.foul_finish,
+            deck: self.deck,
+            round_records,
+            roster_changed: self.roster_changed,
+            ..Game::new(self.game_length, self.scoring)
+        };
+        // Exchanges aren't exported until they're done, so start the next one afresh
+        game.open_exchange();
+        Ok(game)
+    }
+
+    fn check_player(
+        &self,
+        pid: usize,
+        round_records: &[RoundRecord],
+        earlier: &[PlayerExport],
+    ) -> Result<(), ImportError> {
+        let player = &self.players[pid];
+        let name = player.name.as_str();
+        if name.trim().is_empty() {
+            return Err(ImportError::new("a player has no name"));
+        }
+        // The same test as setup uses, so an imported roster can be edited afterwards
+        if earlier.iter().any(|p| same_name(&p.name, name)) {
+            return Err(ImportError::new(format!("{} is listed twice", name.trim())));
+        }
+        let rounds_played = usize::from(self.rounds_played);
+        let joined = rounds_played.saturating_sub(player.ranks.len());
+        if player.ranks.len() > rounds_played || joined > usize::from(self.roster_changed) {
+            return Err(ImportError::new(format!(
+                "{} has ranks for {} rounds, but {} were played",
+                name,
+                player.ranks.len(),
+                self.rounds_played
+            )));
+        }
+        if player.points.len() != player.ranks.len() {
+            return Err(ImportError::new(format!(
+                "{} has points for {} rounds but ranks for {}",
+                name,
+                player.points.len(),
+                player.ranks.len()
+            )));
+        }
+        for (i, (rank, points)) in player.ranks.iter().zip(player.points.iter()).enumerate() {
+            let record = &round_records[joined + i];
+            let revolutions = record.revolutions.iter().filter(|r| r.pid == pid).count();
+            let bonus = self.revolution_bonus.saturating_mul(revolutions as i32);
+            let expected = self.scoring.points(*rank).saturating_add(bonus);
+            // A tycoon who dropped out of the round scores nothing for their rank. Older files
+            // don't say who dropped out, but it was always the Beggar.
+            let dropped_out = self.bankruptcy == Bankruptcy::DropOut
+                && *points == bonus
+                && (record.dropped_out == Some(pid)
+                    || (record.dropped_out.is_none() && *rank == Rank::Beggar));
+            if *points != expected && !dropped_out {
+                return Err(ImportError::new(format!(
+                    "{} was {} in round {}, which is worth {} points, not {}",
+                    name,
+                    rank,
+                    joined + i + 1,
+                    expected,
+                    points
+                )));
+            }
+        }
+        let sum = player.points.iter().fold(0i64, |total, points| {
+            total.saturating_add(i64::from(*points))
+        });
+        if sum != player.total {
+            return Err(ImportError::new(format!(
+                "{} has a total of {}, but their points add up to {}",
+                name, player.total, sum
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Checks that a round's ranks are the ones the table hands out, one of each apart from the
+/// commoners.
+fn check_round(round: usize, ranks: impl Iterator<Item = Rank>) -> Result<(), ImportError> {
+    let ranks: Vec<Rank> = ranks.collect();
+    let num_players = ranks.len();
+    for rank in [
+        Rank::Tycoon,
+        Rank::Rich,
+        Rank::Commoner,
+        Rank::Poor,
+        Rank::Beggar,
+    ]
+    .iter()
+    {
+        let count = ranks.iter().filter(|r| *r == rank).count();
+        let expected = (0..num_players)
+            .filter(|position| Rank::for_position(*position, num_players) == *rank)
+            .count();
+        if count != expected {
+            return Err(ImportError::new(format!(
+                "round {} has {} players ranked {}, but a table of {} should have {}",
+                round + 1,
+                count,
+                rank,
+                num_players,
+                expected
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Why a file couldn't be turned back into a game.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportError {
+    pub message: String,
+}
+
+impl ImportError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for ImportError {}
+
+/// Quotes a field if it would otherwise break the row apart.
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::RosterSeat;
+    use crate::test_util::play_round;
+
+    fn finished_game() -> Game {
+        let mut game = Game::new(GameLength::Rounds(2), ScoringTable::default());
+        game.tie_break = TieBreak::LastRound;
+        game.set_players(&["Alice", "Bob", "Carol, Jr.", "Dave"]);
+        for order in [[0, 1, 2], [2, 3, 1]].iter() {
+            play_round(&mut game, order);
+        }
+        game
+    }
+
+    #[test]
+    fn exports_every_round() {
+        let export = GameExport::new(&finished_game());
+        assert_eq!(export.rounds_played, 2);
+        let alice = &export.players[0];
+        assert_eq!(alice.name, "Alice");
+        assert_eq!(alice.ranks, vec![Rank::Tycoon, Rank::Beggar]);
+        assert_eq!(alice.points, vec![30, 0]);
+        assert_eq!(alice.total, 30);
+    }
+
+    #[test]
+    fn csv_has_a_row_per_player() {
+        let csv = GameExport::new(&finished_game()).to_csv();
+        assert_eq!(
+            csv,
+            "Player,Round 1 Rank,Round 1 Points,Round 2 Rank,Round 2 Points,Total\n\
+             Alice,Tycoon,30,Beggar,0,30\n\
+             Bob,Rich,20,Poor,10,30\n\
+             \"Carol, Jr.\",Poor,10,Tycoon,30,40\n\
+             Dave,Beggar,0,Rich,20,20\n"
+        );
+    }
+
+    #[test]
+    fn imports_what_it_exported() {
+        let game = finished_game();
+        let imported = GameExport::new(&game).into_game().unwrap();
+        assert_eq!(imported, game);
+        assert!(imported.is_game_over());
+    }
+
+    #[test]
+    fn imports_an_unstarted_game() {
+        let mut game = Game::default();
+        game.set_players(&["A", "B", "C"]);
+        assert_eq!(GameExport::new(&game).into_game().unwrap(), game);
+    }
+
+    #[test]
+    fn rejects_two_tycoons_in_a_round() {
+        let mut export = GameExport::new(&finished_game());
+        export.players[1].ranks[0] = Rank::Tycoon;
+        export.players[1].points[0] = 30;
+        export.players[1].total = 40;
+        assert_eq!(
+            export.into_game().unwrap_err().message,
+            "round 1 has 2 players ranked Tycoon, but a table of 4 should have 1"
+        );
+    }
+
+    #[test]
+    fn rejects_inconsistent_players() {
+        let mut export = GameExport::new(&finished_game());
+        export.players[3].total = 100;
+        assert_eq!(
+            export.into_game().unwrap_err().message,
+            "Dave has a total of 100, but their points add up to 20"
+        );
+
+        let mut export = GameExport::new(&finished_game());
+        export.players[0].ranks.pop();
+        assert!(export.into_game().is_err());
+
+        let mut export = GameExport::new(&finished_game());
+        export.players[2].name = "Bob".into();
+        assert_eq!(
+            export.into_game().unwrap_err().message,
+            "Bob is listed twice"
+        );
+
+        let mut export = GameExport::new(&finished_game());
+        export.players[2].name = "bob ".into();
+        assert_eq!(
+            export.into_game().unwrap_err().message,
+            "bob is listed twice"
+        );
+
+        let mut export = GameExport::new(&finished_game());
+        export.players[0].points[1] = 5;
+        assert!(export.into_game().is_err());
+    }
+
+    #[test]
+    fn imports_drop_outs() {
+        let mut game = Game::new(GameLength::Rounds(2), ScoringTable::default());
+        game.bankruptcy = Bankruptcy::DropOut;
+        game.scoring.beggar = -5;
+        game.set_players(&["A", "B", "C"]);
+        for order in [[0, 1], [1, 2]].iter() {
+            play_round(&mut game, order);
+        }
+        let export = GameExport::new(&game);
+        assert_eq!(export.players[0].points, vec![30, 0]);
+        assert_eq!(export.clone().into_game().unwrap(), game);
+
+        let mut export = export;
+        export.bankruptcy = Bankruptcy::Beggar;
+        assert!(export.into_game().is_err());
+    }
+
+    #[test]
+    fn imports_games_with_late_joiners() {
+        let mut game = finished_game();
+        game.game_length = GameLength::Rounds(3);
+        game.edit_roster(&[
+            RosterSeat {
+                name: "Alice".into(),
+                pid: Some(0),
+                bot: None,
+            },
+            RosterSeat {
+                name: "Bob".into(),
+                pid: Some(1),
+                bot: None,
+            },
+            RosterSeat {
+                name: "Eve".into(),
+                pid: None,
+                bot: None,
+            },
+        ]);
+        for pid in [2, 0].iter() {
+            game.handle_go_out(*pid);
+        }
+        game.handle_end_round();
+
+        let export = GameExport::new(&game);
+        assert_eq!(export.players[2].ranks, vec![Rank::Tycoon]);
+        assert!(export.to_csv().contains("\nEve,,,,,Tycoon,30,30\n"));
+        assert_eq!(export.into_game().unwrap(), game);
+    }
+
+    #[test]
+    fn imports_revolution_bonuses() {
+        let mut game = Game::new(GameLength::Rounds(2), ScoringTable::default());
+        game.revolution_bonus = 10;
+        game.set_players(&["A", "B", "C"]);
+        game.handle_revolution(2);
+        // Leave the exchange open, as it is after an import
+        game.handle_go_out(0);
+        game.handle_go_out(1);
+        game.handle_end_round();
+        let export = GameExport::new(&game);
+        assert_eq!(export.players[2].points, vec![10]);
+        assert_eq!(export.clone().into_game().unwrap(), game);
+
+        let mut export = export;
+        export.rounds[0].revolutions.clear();
+        assert_eq!(
+            export.into_game().unwrap_err().message,
+            "C was Beggar in round 1, which is worth 0 points, not 10"
+        );
+    }
+
+    #[test]
+    fn imports_foul_finishes() {
+        let mut game = Game::new(GameLength::Rounds(3), ScoringTable::default());
+        game.foul_finish = true;
+        game.bankruptcy = Bankruptcy::DropOut;
+        game.set_players(&["A", "B", "C", "D"]);
+        play_round(&mut game, &[0, 1, 2]);
+        game.exchanges[0].lower_cards = "2♥ 2♣".into();
+        game.handle_foul_finish(3);
+        game.handle_go_out(1);
+        game.handle_go_out(2);
+        game.handle_end_round();
+        let export = GameExport::new(&game);
+        assert_eq!(export.rounds[1].fouls, vec![3]);
+        assert_eq!(export.rounds[1].dropped_out, Some(0));
+        assert_eq!(export.players[0].ranks[1], Rank::Poor);
+        assert_eq!(export.players[0].points[1], 0);
+        assert_eq!(export.rounds[1].exchanges[0].lower_cards, "2♥ 2♣");
+        assert_eq!(export.into_game().unwrap(), game);
+    }
+
+    #[test]
+    fn rejects_too_few_players() {
+        let mut export = GameExport::new(&finished_game());
+        export.players.truncate(2);
+        assert!(export.into_game().is_err());
+    }
+}
@@ -0,0 +1,1147 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+use crate::bots::Difficulty;
+use crate::deal::{Deal, Deck};
+use crate::exchange::{self, Exchange};
+use crate::player::{Player, Rank, MAX_PLAYERS, MIN_PLAYERS};
+use crate::rules::{Bankruptcy, GameLength, ScoringTable, TieBreak};
+
+/// Ways of listing the players.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum PlayerOrder {
+    #[default]
+    Seat,
+    Score,
+    Rank,
+}
+
+impl PlayerOrder {
+    pub fn all() -> [Self; 3] {
+        [Self::Seat, Self::Score, Self::Rank]
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Seat => "Seat",
+            Self::Score => "Score",
+            Self::Rank => "Rank",
+        }
+    }
+}
+
+/// Where a player finished in the standings.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Standing {
+    pub pid: usize,
+    /// 1 for the winner. Players who share a placing share the number, and the next placing
+    /// skips ahead, so two winners are followed by 3rd.
+    pub place: usize,
+    pub tied: bool,
+}
+
+impl Standing {
+    /// The placing as it's shown to players, such as "2nd" or "1st (tie)".
+    pub fn place_text(&self) -> String {
+        let suffix = match (self.place % 10, self.place % 100) {
+            (_, 11..=13) => "th",
+            (1, _) => "st",
+            (2, _) => "nd",
+            (3, _) => "rd",
+            _ => "th",
+        };
+        if self.tied {
+            format!("{}{} (tie)", self.place, suffix)
+        } else {
+            format!("{}{}", self.place, suffix)
+        }
+    }
+}
+
+/// One seat in an edited list of players.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RosterSeat {
+    pub name: String,
+    /// Who sat here before the edit, or `None` for someone joining.
+    pub pid: Option<usize>,
+    pub bot: Option<Difficulty>,
+}
+
+/// Checks the names for a table: the right number of players, and none blank or repeated.
+pub fn check_names<S: AsRef<str>>(names: &[S]) -> Result<(), String> {
+    if !(MIN_PLAYERS..=MAX_PLAYERS).contains(&names.len()) {
+        return Err(format!(
+            "Tycoon needs between {} and {} players.",
+            MIN_PLAYERS, MAX_PLAYERS
+        ));
+    }
+    for (i, name) in names.iter().enumerate() {
+        let name = name.as_ref().trim();
+        if name.is_empty() {
+            return Err("Every player needs a name.".into());
+        }
+        if names[..i]
+            .iter()
+            .any(|other| same_name(other.as_ref(), name))
+        {
+            return Err(format!("There's more than one player called {}.", name));
+        }
+    }
+    Ok(())
+}
+
+/// Whether two names are for the same player, ignoring case and spaces at either end.
+pub fn same_name(a: &str, b: &str) -> bool {
+    a.trim().to_lowercase() == b.trim().to_lowercase()
+}
+
+/// A revolution (kakumei) played during a round, which flips the strength of the cards.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Revolution {
+    pub pid: usize,
+    /// Whether card strength is reversed after it. A second revolution puts it back.
+    pub reversed: bool,
+}
+
+/// What happened in a finished round, beyond everyone's rank.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RoundRecord {
+    pub revolutions: Vec<Revolution>,
+    /// Players who went out on an illegal card, in the order they did.
+    #[serde(default)]
+    pub fouls: Vec<usize>,
+    /// The old Tycoon, if they dropped out of the round under `Bankruptcy::DropOut`.
+    #[serde(default)]
+    pub dropped_out: Option<usize>,
+    /// The card exchanges made before the round.
+    #[serde(default)]
+    pub exchanges: Vec<Exchange>,
+}
+
+/// A game in progress: the players, who has gone out so far this round, and the rules chosen at
+/// setup.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Game {
+    /// In seating order, the order they were entered at setup. A player's id is their seat.
+    pub players: Vec<Player>,
+    pub round: u16,
+    pub game_length: GameLength,
+    #[serde(default)]
+    pub scoring: ScoringTable,
+    #[serde(default)]
+    pub tie_break: TieBreak,
+    #[serde(default)]
+    pub bankruptcy: Bankruptcy,
+    /// Points for each revolution a player triggers, on top of their rank.
+    #[serde(default)]
+    pub revolution_bonus: i32,
+    /// Whether going out on an illegal card (a Joker, a 2, an 8 or the 3 of spades) sends the
+    /// player to the bottom.
+    #[serde(default)]
+    pub foul_finish: bool,
+    #[serde(default)]
+    pub deck: Deck,
+    pub players_out: HashMap<usize, Rank>,
+    pub beggar_message: Option<String>,
+    /// The old Tycoon, if they dropped out of this round under `Bankruptcy::DropOut`.
+    #[serde(default)]
+    pub dropped_out: Option<usize>,
+    /// The old Tycoon, if they went bankrupt this round.
+    #[serde(default)]
+    pub bankrupt: Option<usize>,
+    /// Players who fouled out this round, in the order they did.
+    #[serde(default)]
+    pub fouls: Vec<usize>,
+    /// Revolutions so far this round.
+    #[serde(default)]
+    pub revolutions: Vec<Revolution>,
+    /// The card exchanges before this round.
+    #[serde(default)]
+    pub exchanges: Vec<Exchange>,
+    /// Whether the players are still swapping cards, so nobody can go out yet.
+    #[serde(default)]
+    pub exchanging: bool,
+    /// One for each round played, first round first.
+    #[serde(default)]
+    pub round_records: Vec<RoundRecord>,
+    /// Rounds played when someone last joined or left partway through the game. Rounds before
+    /// that were played at a different table.
+    #[serde(default)]
+    pub roster_changed: u16,
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Self {
+            players: vec![],
+            round: 1,
+            game_length: GameLength::default(),
+            scoring: ScoringTable::default(),
+            tie_break: TieBreak::default(),
+            bankruptcy: Bankruptcy::default(),
+            revolution_bonus: 0,
+            foul_finish: false,
+            deck: Deck::default(),
+            players_out: HashMap::new(),
+            beggar_message: None,
+            dropped_out: None,
+            bankrupt: None,
+            fouls: vec![],
+            revolutions: vec![],
+            exchanges: vec![],
+            exchanging: false,
+            round_records: vec![],
+            roster_changed: 0,
+        }
+    }
+}
+
+impl Game {
+    pub fn new(game_length: GameLength, scoring: ScoringTable) -> Self {
+        Self {
+            game_length,
+            scoring,
+            ..Self::default()
+        }
+    }
+
+    /// Replaces the players with new ones, numbered in the order given, and starts a new game.
+    pub fn set_players<S: AsRef<str>>(&mut self, names: &[S]) {
+        self.players = names
+            .iter()
+            .map(|name| Player::new(name.as_ref()))
+            .collect();
+        self.new_game();
+    }
+
+    /// Changes the players partway through a game, keeping everyone's scores and ranks. Renaming
+    /// and reseating players carries on with the round in progress, but if anyone joins or leaves
+    /// the round starts again.
+    pub fn edit_roster(&mut self, seats: &[RosterSeat]) {
+        let mut pids: Vec<Option<usize>> = seats.iter().map(|s| s.pid).collect();
+        pids.sort();
+        let same_table = pids.iter().copied().eq((0..self.players.len()).map(Some));
+
+        let players: Vec<Player> = seats
+            .iter()
+            .map(|seat| {
+                let mut player = match seat.pid.and_then(|pid| self.players.get(pid)) {
+                    Some(player) => player.clone(),
+                    None => Player::new(""),
+                };
+                player.name = seat.name.trim().to_string();
+                player.bot = seat.bot;
+                player
+            })
+            .collect();
+        let new_seat = |old: usize| seats.iter().position(|s| s.pid == Some(old));
+        let move_exchanges = |exchanges: &[Exchange]| -> Vec<Exchange> {
+            exchanges
+                .iter()
+                .filter_map(|e| {
+                    Some(Exchange {
+                        upper: new_seat(e.upper)?,
+                        lower: new_seat(e.lower)?,
+                        ..e.clone()
+                    })
+                })
+                .collect()
+        };
+        let move_revolutions = |revolutions: &[Revolution]| -> Vec<Revolution> {
+            revolutions
+                .iter()
+                .filter_map(|r| {
+                    Some(Revolution {
+                        pid: new_seat(r.pid)?,
+                        ..*r
+                    })
+                })
+                .collect()
+        };
+        for record in self.round_records.iter_mut() {
+            record.revolutions = move_revolutions(&record.revolutions);
+            record.fouls = record
+                .fouls
+                .iter()
+                .filter_map(|pid| new_seat(*pid))
+                .collect();
+            record.dropped_out = record.dropped_out.and_then(new_seat);
+            record.exchanges = move_exchanges(&record.exchanges);
+        }
+        self.exchanges = move_exchanges(&self.exchanges);
+        if same_table {
+            self.players_out = self
+                .players_out
+                .iter()
+                .filter_map(|(old, rank)| Some((new_seat(*old)?, *rank)))
+                .collect();
+            self.dropped_out = self.dropped_out.and_then(new_seat);
+            self.bankrupt = self.bankrupt.and_then(new_seat);
+            self.fouls = self.fouls.iter().filter_map(|pid| new_seat(*pid)).collect();
+            self.revolutions = move_revolutions(&self.revolutions);
+        } else {
+            self.restart_round();
+            self.roster_changed = self.round - 1;
+        }
+        self.players = players;
+        // With different players at the table, the swaps have to be worked out again
+        if self.exchanging && !same_table {
+            self.open_exchange();
+        }
+    }
+
+    /// Forgets everything that has happened so far in the round in progress.
+    pub fn restart_round(&mut self) {
+        self.players_out = HashMap::new();
+        self.beggar_message = None;
+        self.dropped_out = None;
+        self.bankrupt = None;
+        self.fouls = vec![];
+        self.revolutions = vec![];
+    }
+
+    /// Whether any seats are played by the computer, in which case the round is played out with
+    /// real cards.
+    pub fn has_bots(&self) -> bool {
+        self.players.iter().any(|p| p.bot.is_some())
+    }
+
+    pub fn find_tycoon(&self) -> Option<usize> {
+        self.players.iter().position(Player::is_tycoon)
+    }
+
+    pub fn find_last_not_out(&self) -> Option<usize> {
+        (0..self.players.len()).find(|i| !self.players_out.contains_key(i))
+    }
+
+    /// Number of players sent to the bottom of the table this round, by going bankrupt or
+    /// fouling out.
+    fn sent_down(&self) -> usize {
+        self.fouls.len() + usize::from(self.bankrupt.is_some())
+    }
+
+    /// Number of players who have taken a rank from the top of the table this round. Players
+    /// sent to the bottom don't count.
+    fn finishing_position(&self) -> usize {
+        self.players_out.len() - self.sent_down()
+    }
+
+    /// The rank for the next player sent to the bottom: the lowest one nobody has yet.
+    fn bottom_rank(&self) -> Rank {
+        let num_players = self.players.len();
+        Rank::for_position(num_players - 1 - self.sent_down(), num_players)
+    }
+
+    /// Records a player going out on an illegal card, which puts them below everyone still
+    /// playing. Does nothing unless the foul finish rule is on.
+    pub fn handle_foul_finish(&mut self, pid: usize) {
+        if !self.foul_finish
+            || self.exchanging
+            || self.players_out.contains_key(&pid)
+            || pid >= self.players.len()
+        {
+            return;
+        }
+        self.go_out(pid, true);
+    }
+
+    pub fn handle_go_out(&mut self, pid: usize) {
+        if self.exchanging || self.players_out.contains_key(&pid) || pid >= self.players.len() {
+            return;
+        }
+        self.go_out(pid, false);
+    }
+
+    fn go_out(&mut self, pid: usize, foul: bool) {
+        let num_players = self.players.len();
+        let position = self.finishing_position();
+        if foul {
+            let rank = self.bottom_rank();
+            self.players_out.insert(pid, rank);
+            self.fouls.push(pid);
+        } else {
+            self.players_out
+                .insert(pid, Rank::for_position(position, num_players));
+        }
+        // First person to go out is tycoon. period. In round one this has no side effects, but
+        // otherwise if the person who went out is not already the tycoon, the tycoon goes
+        // bankrupt and is automatically the beggar, unless the rules say otherwise
+        if position == 0 && !foul && self.round != 1 && self.bankruptcy != Bankruptcy::Off {
+            match self.find_tycoon() {
+                // A tycoon who already fouled out is at the bottom anyway
+                Some(tid) if tid != pid && !self.players_out.contains_key(&tid) => {
+                    let rank = self.bottom_rank();
+                    self.players_out.insert(tid, rank);
+                    self.bankrupt = Some(tid);
+                    let new_tycoon_name = match self.players.get(pid) {
+                        Some(x) => x.name.as_str(),
+                        None => "",
+                    };
+                    let new_beggar_name = match self.players.get(tid) {
+                        Some(x) => x.name.as_str(),
+                        None => "",
+                    };
+                    self.beggar_message = Some(match self.bankruptcy {
+                        Bankruptcy::DropOut => {
+                            self.dropped_out = Some(tid);
+                            format!(
+                                "{} went out so {} drops out of the round as the {}, and scores nothing.",
+                                new_tycoon_name,
+                                new_beggar_name,
+                                rank.to_string().to_lowercase()
+                            )
+                        }
+                        _ => format!(
+                            "{} went out so {} is the {} automatically.",
+                            new_tycoon_name,
+                            new_beggar_name,
+                            rank.to_string().to_lowercase()
+                        ),
+                    });
+                }
+                _ => (),
+            }
+        }
+        // Once everyone but one player is out, the last player takes whatever rank is left
+        if self.players_out.len() + 1 == num_players {
+            if let Some(lid) = self.find_last_not_out() {
+                let position = self.finishing_position();
+                self.players_out
+                    .insert(lid, Rank::for_position(position, num_players));
+            }
+        }
+    }
+
+    /// Whether every player has a rank for this round, so it can be ended.
+    pub fn is_round_complete(&self) -> bool {
+        !self.players.is_empty() && self.players_out.len() == self.players.len()
+    }
+
+    /// Whether the strength of the cards is currently reversed by a revolution.
+    pub fn is_reversed(&self) -> bool {
+        self.revolutions.last().is_some_and(|r| r.reversed)
+    }
+
+    /// Records a revolution by a player who is still in the round.
+    pub fn handle_revolution(&mut self, pid: usize) {
+        if self.exchanging || pid >= self.players.len() || self.players_out.contains_key(&pid) {
+            return;
+        }
+        let reversed = !self.is_reversed();
+        self.revolutions.push(Revolution { pid, reversed });
+    }
+
+    pub fn handle_end_round(&mut self) {
+        for (i, player) in self.players.iter_mut().enumerate() {
+            if let Some(r) = self.players_out.get(&i) {
+                player.set_rank(*r);
+                let points = if self.dropped_out == Some(i) {
+                    0
+                } else {
+                    self.scoring.points(*r)
+                };
+                let revolutions = self.revolutions.iter().filter(|r| r.pid == i).count();
+                let bonus = self.revolution_bonus.saturating_mul(revolutions as i32);
+                player.add_points(points.saturating_add(bonus));
+            }
+        }
+        self.round_records.push(RoundRecord {
+            revolutions: std::mem::take(&mut self.revolutions),
+            fouls: std::mem::take(&mut self.fouls),
+            dropped_out: self.dropped_out,
+            exchanges: std::mem::take(&mut self.exchanges),
+        });
+        self.players_out = HashMap::new();
+        self.round += 1;
+        self.beggar_message = None;
+        self.dropped_out = None;
+        self.bankrupt = None;
+        self.open_exchange();
+    }
+
+    /// Sets up the card exchanges for the next round, if the game isn't over.
+    pub(crate) fn open_exchange(&mut self) {
+        self.exchanges = if self.is_game_over() {
+            vec![]
+        } else {
+            exchange::checklist(&self.players)
+        };
+        self.exchanging = !self.exchanges.is_empty();
+    }
+
+    /// How the deck splits between the players for this round.
+    pub fn deal(&self) -> Deal {
+        Deal::new(self.deck, &self.players)
+    }
+
+    /// Whether every card exchange before this round has been confirmed.
+    pub fn is_exchange_complete(&self) -> bool {
+        self.exchanges.iter().all(|e| e.done)
+    }
+
+    /// Marks one of the exchanges on the checklist as done, or not done after all.
+    pub fn confirm_exchange(&mut self, index: usize, done: bool) {
+        if !self.exchanging {
+            return;
+        }
+        if let Some(exchange) = self.exchanges.get_mut(index) {
+            exchange.done = done;
+        }
+    }
+
+    /// Marks every exchange on the checklist as done.
+    pub fn confirm_all_exchanges(&mut self) {
+        if !self.exchanging {
+            return;
+        }
+        for exchange in self.exchanges.iter_mut() {
+            exchange.done = true;
+        }
+    }
+
+    /// Writes down the cards one side of an exchange passed.
+    pub fn record_exchange_cards(&mut self, index: usize, upper: bool, cards: &str) {
+        if let Some(exchange) = self.exchanges.get_mut(index) {
+            let side = if upper {
+                &mut exchange.upper_cards
+            } else {
+                &mut exchange.lower_cards
+            };
+            *side = cards.trim().to_string();
+        }
+    }
+
+    /// Ends the exchange so players can start going out, once every exchange is confirmed.
+    pub fn start_round(&mut self) {
+        if self.is_exchange_complete() {
+            self.exchanging = false;
+        }
+    }
+
+    pub fn is_game_over(&self) -> bool {
+        let top_score = self.players.iter().map(|p| p.score).max().unwrap_or(0);
+        self.game_length.is_over(self.round - 1, top_score)
+    }
+
+    pub fn get_ranking(&self) -> Vec<Player> {
+        self.standings()
+            .into_iter()
+            .map(|s| self.players[s.pid].clone())
+            .collect()
+    }
+
+    /// Everyone's placing by score, with ties settled by `tie_break`. Players it can't separate
+    /// share a placing and stay in seating order.
+    pub fn standings(&self) -> Vec<Standing> {
+        let key = |pid: usize| {
+            let player = &self.players[pid];
+            let tie_break = match self.tie_break {
+                TieBreak::MostTycoons => player
+                    .past_ranks
+                    .iter()
+                    .chain(player.rank.iter())
+                    .filter(|r| **r == Rank::Tycoon)
+                    .count(),
+                TieBreak::LastRound => player.rank.map_or(0, |r| r as usize + 1),
+                TieBreak::Shared => 0,
+            };
+            Reverse((player.score, tie_break))
+        };
+        let mut pids: Vec<usize> = (0..self.players.len()).collect();
+        pids.sort_by_key(|pid| key(*pid));
+        pids.iter()
+            .map(|pid| {
+                let place = pids
+                    .iter()
+                    .position(|other| key(*other) == key(*pid))
+                    .unwrap()
+                    + 1;
+                let tied = pids
+                    .iter()
+                    .filter(|other| key(**other) == key(*pid))
+                    .count()
+                    > 1;
+                Standing {
+                    pid: *pid,
+                    place,
+                    tied,
+                }
+            })
+            .collect()
+    }
+
+    /// Player ids sorted for display. Players who are level stay in seating order, so the list
+    /// never jumps around between renders.
+    pub fn player_order(&self, order: PlayerOrder) -> Vec<usize> {
+        let mut pids: Vec<usize> = (0..self.players.len()).collect();
+        match order {
+            PlayerOrder::Seat => (),
+            PlayerOrder::Score => pids.sort_by_key(|pid| Reverse(self.players[*pid].score)),
+            // Unranked players sort below the beggar
+            PlayerOrder::Rank => pids.sort_by_key(|pid| Reverse(self.players[*pid].rank)),
+        }
+        pids
+    }
+
+    pub fn new_game(&mut self) {
+        for player in self.players.iter_mut() {
+            player.score = 0;
+            player.rank = None;
+            player.past_ranks = vec![];
+            player.round_points = vec![];
+        }
+        self.round = 1;
+        self.players_out = HashMap::new();
+        self.beggar_message = None;
+        self.dropped_out = None;
+        self.bankrupt = None;
+        self.fouls = vec![];
+        self.revolutions = vec![];
+        self.exchanges = vec![];
+        self.exchanging = false;
+        self.round_records = vec![];
+        self.roster_changed = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{finish_exchange, game_with, play_round};
+
+    fn ranks(game: &Game) -> Vec<Rank> {
+        game.players.iter().map(|p| p.rank.unwrap()).collect()
+    }
+
+    #[test]
+    fn first_round_ranks_by_finishing_order() {
+        let mut game = game_with(4);
+        game.handle_go_out(2);
+        game.handle_go_out(0);
+        assert!(!game.is_round_complete());
+        game.handle_go_out(3);
+        // The last player doesn't need to be sent out by hand
+        assert!(game.is_round_complete());
+        assert_eq!(game.players_out[&2], Rank::Tycoon);
+        assert_eq!(game.players_out[&0], Rank::Rich);
+        assert_eq!(game.players_out[&3], Rank::Poor);
+        assert_eq!(game.players_out[&1], Rank::Beggar);
+        assert_eq!(game.beggar_message, None);
+    }
+
+    #[test]
+    fn no_bankruptcy_in_round_one() {
+        let mut game = game_with(4);
+        game.players[0].rank = Some(Rank::Tycoon);
+        game.handle_go_out(1);
+        assert_eq!(game.players_out.len(), 1);
+        assert_eq!(game.beggar_message, None);
+    }
+
+    #[test]
+    fn first_round_scores() {
+        let mut game = game_with(4);
+        play_round(&mut game, &[0, 1, 2]);
+        assert_eq!(game.round, 2);
+        assert_eq!(
+            ranks(&game),
+            vec![Rank::Tycoon, Rank::Rich, Rank::Poor, Rank::Beggar]
+        );
+        let scores: Vec<i64> = (0..4).map(|i| game.players[i].score).collect();
+        assert_eq!(scores, vec![30, 20, 10, 0]);
+        assert!(game.players_out.is_empty());
+    }
+
+    #[test]
+    fn tycoon_going_out_first_keeps_their_seat() {
+        let mut game = game_with(4);
+        play_round(&mut game, &[0, 1, 2]);
+        game.handle_go_out(0);
+        assert_eq!(game.players_out.len(), 1);
+        assert_eq!(game.beggar_message, None);
+        play_round(&mut game, &[3, 2]);
+        assert_eq!(
+            ranks(&game),
+            vec![Rank::Tycoon, Rank::Beggar, Rank::Poor, Rank::Rich]
+        );
+    }
+
+    #[test]
+    fn bankrupt_tycoon_becomes_beggar() {
+        let mut game = game_with(4);
+        play_round(&mut game, &[0, 1, 2]);
+        game.handle_go_out(2);
+        assert_eq!(game.players_out[&2], Rank::Tycoon);
+        assert_eq!(game.players_out[&0], Rank::Beggar);
+        assert_eq!(
+            game.beggar_message,
+            Some("P2 went out so P0 is the beggar automatically.".to_string())
+        );
+        game.handle_go_out(3);
+        assert!(game.is_round_complete());
+        assert_eq!(game.players_out[&3], Rank::Rich);
+        assert_eq!(game.players_out[&1], Rank::Poor);
+        game.handle_end_round();
+        assert_eq!(game.beggar_message, None);
+        let scores: Vec<i64> = (0..4).map(|i| game.players[i].score).collect();
+        assert_eq!(scores, vec![30, 30, 40, 20]);
+    }
+
+    #[test]
+    fn no_bankruptcy_when_it_is_off() {
+        let mut game = game_with(4);
+        game.bankruptcy = Bankruptcy::Off;
+        play_round(&mut game, &[0, 1, 2]);
+        game.handle_go_out(2);
+        assert_eq!(game.players_out.len(), 1);
+        assert_eq!(game.beggar_message, None);
+        play_round(&mut game, &[0, 1]);
+        assert_eq!(
+            ranks(&game),
+            vec![Rank::Rich, Rank::Poor, Rank::Tycoon, Rank::Beggar]
+        );
+    }
+
+    #[test]
+    fn bankrupt_tycoon_drops_out() {
+        let mut game = game_with(4);
+        game.bankruptcy = Bankruptcy::DropOut;
+        game.scoring = ScoringTable::preset("Plus/minus (2/1/0/-1)").unwrap();
+        play_round(&mut game, &[3, 1, 2]);
+        game.handle_go_out(2);
+        assert_eq!(game.players_out[&3], Rank::Beggar);
+        assert_eq!(
+            game.beggar_message,
+            Some(
+                "P2 went out so P3 drops out of the round as the beggar, and scores nothing."
+                    .to_string()
+            )
+        );
+        play_round(&mut game, &[0]);
+        assert_eq!(
+            ranks(&game),
+            vec![Rank::Rich, Rank::Poor, Rank::Tycoon, Rank::Beggar]
+        );
+        // The old tycoon keeps their 2 points rather than losing one as the beggar
+        let scores: Vec<i64> = game.players.iter().map(|p| p.score).collect();
+        assert_eq!(scores, vec![0, 1, 2, 2]);
+        assert_eq!(game.players[3].round_points, vec![2, 0]);
+        assert_eq!(game.dropped_out, None);
+    }
+
+    #[test]
+    fn revolutions_flip_the_cards_and_earn_a_bonus() {
+        let mut game = game_with(4);
+        game.revolution_bonus = 5;
+        assert!(!game.is_reversed());
+        game.handle_revolution(1);
+        assert!(game.is_reversed());
+        game.handle_go_out(0);
+        // Players who are out can't play any more cards
+        game.handle_revolution(0);
+        game.handle_revolution(1);
+        assert!(!game.is_reversed());
+        assert_eq!(game.revolutions.len(), 2);
+        play_round(&mut game, &[1, 2]);
+        let scores: Vec<i64> = game.players.iter().map(|p| p.score).collect();
+        assert_eq!(scores, vec![30, 30, 10, 0]);
+        assert_eq!(
+            game.round_records[0].revolutions,
+            vec![
+                Revolution {
+                    pid: 1,
+                    reversed: true
+                },
+                Revolution {
+                    pid: 1,
+                    reversed: false
+                }
+            ]
+        );
+        assert!(game.revolutions.is_empty());
+        assert!(!game.is_reversed());
+    }
+
+    #[test]
+    fn foul_finishes_go_to_the_bottom() {
+        let mut game = game_with(5);
+        // The rule is off unless it's chosen at setup
+        game.handle_foul_finish(0);
+        assert!(game.players_out.is_empty());
+
+        game.foul_finish = true;
+        game.handle_foul_finish(0);
+        assert_eq!(game.players_out[&0], Rank::Beggar);
+        game.handle_go_out(1);
+        assert_eq!(game.players_out[&1], Rank::Tycoon);
+        game.handle_foul_finish(2);
+        assert_eq!(game.players_out[&2], Rank::Poor);
+        game.handle_go_out(3);
+        assert_eq!(game.players_out[&3], Rank::Rich);
+        assert_eq!(game.players_out[&4], Rank::Commoner);
+        game.handle_end_round();
+        assert_eq!(game.round_records[0].fouls, vec![0, 2]);
+        assert!(game.fouls.is_empty());
+    }
+
+    #[test]
+    fn foul_finishes_with_bankruptcy() {
+        let mut game = game_with(4);
+        game.foul_finish = true;
+        play_round(&mut game, &[0, 1, 2]);
+        // Someone fouls before anyone has gone out, so the bankrupt tycoon lands above them
+        game.handle_foul_finish(3);
+        game.handle_go_out(1);
+        assert_eq!(game.players_out[&3], Rank::Beggar);
+        assert_eq!(game.players_out[&1], Rank::Tycoon);
+        assert_eq!(game.players_out[&0], Rank::Poor);
+        assert_eq!(game.players_out[&2], Rank::Rich);
+        assert_eq!(
+            game.beggar_message.as_deref(),
+            Some("P1 went out so P0 is the poor automatically.")
+        );
+        game.handle_end_round();
+        finish_exchange(&mut game);
+
+        // A tycoon who fouls out doesn't also go bankrupt
+        game.handle_foul_finish(1);
+        game.handle_go_out(2);
+        assert_eq!(game.players_out[&1], Rank::Beggar);
+        assert_eq!(game.players_out[&2], Rank::Tycoon);
+        assert_eq!(game.beggar_message, None);
+    }
+
+    #[test]
+    fn exchanges_come_before_the_next_round() {
+        let mut game = game_with(4);
+        for pid in [2, 0, 1].iter() {
+            game.handle_go_out(*pid);
+        }
+        game.handle_end_round();
+        assert!(game.exchanging);
+        let pairs: Vec<(usize, usize, usize)> = game
+            .exchanges
+            .iter()
+            .map(|e| (e.upper, e.lower, e.count))
+            .collect();
+        assert_eq!(pairs, vec![(2, 3, 2), (0, 1, 1)]);
+
+        // Nobody can go out until every exchange is confirmed
+        game.handle_go_out(0);
+        assert!(game.players_out.is_empty());
+        game.confirm_exchange(0, true);
+        game.record_exchange_cards(0, false, " 2♠, Joker ");
+        game.start_round();
+        assert!(game.exchanging);
+        game.confirm_exchange(1, true);
+        game.start_round();
+        assert!(!game.exchanging);
+        game.handle_go_out(2);
+        assert_eq!(game.players_out.len(), 1);
+
+        for pid in [0, 1].iter() {
+            game.handle_go_out(*pid);
+        }
+        game.handle_end_round();
+        let record = &game.round_records[1];
+        assert_eq!(record.exchanges[0].lower_cards, "2♠, Joker");
+        assert!(record.exchanges.iter().all(|e| e.done));
+        assert!(game.exchanges.iter().all(|e| !e.done));
+    }
+
+    #[test]
+    fn no_exchange_once_the_game_is_over() {
+        let mut game = game_with(3);
+        game.game_length = GameLength::Rounds(1);
+        play_round(&mut game, &[0, 1]);
+        assert!(game.is_game_over());
+        assert!(game.exchanges.is_empty());
+        assert!(!game.exchanging);
+    }
+
+    #[test]
+    fn bankruptcy_in_a_three_player_game() {
+        let mut game = game_with(3);
+        play_round(&mut game, &[0, 1]);
+        assert_eq!(
+            ranks(&game),
+            vec![Rank::Tycoon, Rank::Commoner, Rank::Beggar]
+        );
+        game.handle_go_out(1);
+        assert!(game.is_round_complete());
+        assert_eq!(game.players_out[&1], Rank::Tycoon);
+        assert_eq!(game.players_out[&0], Rank::Beggar);
+        assert_eq!(game.players_out[&2], Rank::Commoner);
+    }
+
+    #[test]
+    fn commoners_fill_the_middle_of_big_tables() {
+        let mut game = game_with(6);
+        play_round(&mut game, &[5, 4, 3, 2, 1]);
+        assert_eq!(
+            ranks(&game),
+            vec![
+                Rank::Beggar,
+                Rank::Poor,
+                Rank::Commoner,
+                Rank::Commoner,
+                Rank::Rich,
+                Rank::Tycoon
+            ]
+        );
+    }
+
+    #[test]
+    fn bankruptcy_at_a_big_table() {
+        let mut game = game_with(5);
+        play_round(&mut game, &[0, 1, 2, 3]);
+        play_round(&mut game, &[4, 3, 2]);
+        assert_eq!(
+            ranks(&game),
+            vec![
+                Rank::Beggar,
+                Rank::Poor,
+                Rank::Commoner,
+                Rank::Rich,
+                Rank::Tycoon
+            ]
+        );
+    }
+
+    #[test]
+    fn going_out_twice_or_unknown_players_is_ignored() {
+        let mut game = game_with(4);
+        game.handle_go_out(0);
+        game.handle_go_out(0);
+        game.handle_go_out(17);
+        assert_eq!(game.players_out.len(), 1);
+    }
+
+    #[test]
+    fn game_over_after_chosen_rounds() {
+        let mut game = game_with(4);
+        game.game_length = GameLength::Rounds(2);
+        play_round(&mut game, &[0, 1, 2]);
+        assert!(!game.is_game_over());
+        play_round(&mut game, &[0, 1, 2]);
+        assert!(game.is_game_over());
+    }
+
+    #[test]
+    fn game_over_when_someone_reaches_target() {
+        let mut game = game_with(4);
+        game.game_length = GameLength::Points(70);
+        play_round(&mut game, &[0, 1, 2]);
+        play_round(&mut game, &[0, 1, 2]);
+        assert!(!game.is_game_over());
+        play_round(&mut game, &[0, 1, 2]);
+        assert!(game.is_game_over());
+    }
+
+    #[test]
+    fn points_games_can_run_past_255_rounds() {
+        let mut game = game_with(4);
+        game.game_length = GameLength::Points(u16::MAX);
+        game.scoring = ScoringTable::parse("0/0/0/0/0").unwrap();
+        for _ in 0..300 {
+            play_round(&mut game, &[0, 1, 2]);
+        }
+        assert_eq!(game.round, 301);
+        assert!(!game.is_game_over());
+        assert_eq!(game.round_records.len(), 300);
+    }
+
+    #[test]
+    fn ranking_is_by_score() {
+        let mut game = game_with(4);
+        play_round(&mut game, &[3, 2, 1]);
+        let names: Vec<String> = game.get_ranking().into_iter().map(|p| p.name).collect();
+        assert_eq!(names, vec!["P3", "P2", "P1", "P0"]);
+    }
+
+    #[test]
+    fn players_stay_in_seating_order() {
+        let mut game = Game::default();
+        game.set_players(&["Dave", "Alice", "Carol", "Bob"]);
+        let names: Vec<&str> = game.players.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["Dave", "Alice", "Carol", "Bob"]);
+    }
+
+    #[test]
+    fn player_orders() {
+        let mut game = game_with(4);
+        assert_eq!(game.player_order(PlayerOrder::Rank), vec![0, 1, 2, 3]);
+        play_round(&mut game, &[2, 3, 0]);
+        game.handle_go_out(3);
+        game.handle_go_out(1);
+        play_round(&mut game, &[2]);
+        // Scores are 20, 20, 30, 50 and the last round went Poor, Rich, Beggar, Tycoon
+        assert_eq!(game.player_order(PlayerOrder::Seat), vec![0, 1, 2, 3]);
+        assert_eq!(game.player_order(PlayerOrder::Score), vec![3, 2, 0, 1]);
+        assert_eq!(game.player_order(PlayerOrder::Rank), vec![3, 1, 0, 2]);
+    }
+
+    fn places(game: &Game) -> Vec<(usize, String)> {
+        game.standings()
+            .iter()
+            .map(|s| (s.pid, s.place_text()))
+            .collect()
+    }
+
+    /// P0 and P1 finish level on 30. P0 was Tycoon once, but went bankrupt in the last round.
+    fn tied_game(tie_break: TieBreak) -> Game {
+        let mut game = game_with(4);
+        game.tie_break = tie_break;
+        play_round(&mut game, &[0, 1, 2]);
+        play_round(&mut game, &[2, 3]);
+        game
+    }
+
+    #[test]
+    fn shared_placings() {
+        let game = tied_game(TieBreak::Shared);
+        let scores: Vec<i64> = game.players.iter().map(|p| p.score).collect();
+        assert_eq!(scores, vec![30, 30, 40, 20]);
+        assert_eq!(
+            places(&game),
+            vec![
+                (2, "1st".to_string()),
+                (0, "2nd (tie)".to_string()),
+                (1, "2nd (tie)".to_string()),
+                (3, "4th".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn ties_broken_by_tycoon_finishes() {
+        let game = tied_game(TieBreak::MostTycoons);
+        assert_eq!(
+            places(&game),
+            vec![
+                (2, "1st".to_string()),
+                (0, "2nd".to_string()),
+                (1, "3rd".to_string()),
+                (3, "4th".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn ties_broken_by_the_last_round() {
+        let game = tied_game(TieBreak::LastRound);
+        assert_eq!(places(&game)[1], (1, "2nd".to_string()));
+        assert_eq!(places(&game)[2], (0, "3rd".to_string()));
+    }
+
+    #[test]
+    fn unbreakable_ties_are_shared() {
+        let mut game = game_with(3);
+        game.tie_break = TieBreak::MostTycoons;
+        assert_eq!(
+            places(&game),
+            vec![
+                (0, "1st (tie)".to_string()),
+                (1, "1st (tie)".to_string()),
+                (2, "1st (tie)".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn place_suffixes() {
+        let text = |place| {
+            Standing {
+                pid: 0,
+                place,
+                tied: false,
+            }
+            .place_text()
+        };
+        assert_eq!(text(2), "2nd");
+        assert_eq!(text(3), "3rd");
+        assert_eq!(text(11), "11th");
+        assert_eq!(text(21), "21st");
+    }
+
+    fn seat(name: &str, pid: Option<usize>) -> RosterSeat {
+        RosterSeat {
+            name: name.into(),
+            pid,
+            bot: None,
+        }
+    }
+
+    #[test]
+    fn names_are_checked() {
+        assert!(check_names(&["A", "B", "C"]).is_ok());
+        assert!(check_names(&["A", "B"]).is_err());
+        assert_eq!(
+            check_names(&["A", " ", "C"]),
+            Err("Every player needs a name.".to_string())
+        );
+        assert_eq!(
+            check_names(&["Alice", "Bob", "alice "]),
+            Err("There's more than one player called alice.".to_string())
+        );
+    }
+
+    #[test]
+    fn renaming_and_reseating_keeps_the_round_going() {
+        let mut game = game_with(4);
+        play_round(&mut game, &[0, 1, 2]);
+        game.handle_go_out(1);
+        game.edit_roster(&[
+            seat("P3", Some(3)),
+            seat("Alice", Some(0)),
+            seat("P1", Some(1)),
+            seat("P2", Some(2)),
+        ]);
+        assert_eq!(game.players[1].name, "Alice");
+        assert_eq!(game.players[1].score, 30);
+        assert_eq!(game.players_out.get(&2), Some(&Rank::Tycoon));
+        assert_eq!(game.players_out.get(&1), Some(&Rank::Beggar));
+        assert_eq!(game.roster_changed, 0);
+    }
+
+    #[test]
+    fn joining_and_leaving_restarts_the_round() {
+        let mut game = game_with(4);
+        play_round(&mut game, &[0, 1, 2]);
+        play_round(&mut game, &[0, 1, 2]);
+        game.handle_go_out(3);
+        game.edit_roster(&[
+            seat("P0", Some(0)),
+            seat("P2", Some(2)),
+            seat("P3", Some(3)),
+            seat("Eve", None),
+        ]);
+        assert!(game.players_out.is_empty());
+        assert_eq!(game.beggar_message, None);
+        assert_eq!(game.roster_changed, 2);
+        assert_eq!(game.players[1].score, 20);
+        assert_eq!(game.players[3].name, "Eve");
+        assert_eq!(game.players[3].score, 0);
+        // The new table plays on with the old tycoon still at risk of going bankrupt
+        game.handle_go_out(3);
+        assert_eq!(game.players_out.get(&0), Some(&Rank::Beggar));
+    }
+
+    #[test]
+    fn new_game_resets_scores_and_ranks() {
+        let mut game = game_with(4);
+        play_round(&mut game, &[0, 1, 2]);
+        game.handle_go_out(1);
+        game.new_game();
+        assert_eq!(game.round, 1);
+        assert!(game.players_out.is_empty());
+        assert_eq!(game.beggar_message, None);
+        for player in game.players.iter() {
+            assert_eq!(player.score, 0);
+            assert_eq!(player.rank, None);
+            assert!(player.past_ranks.is_empty());
+            assert!(player.round_points.is_empty());
+        }
+    }
+}
@@ -0,0 +1,189 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+use crate::game::Game;
+use crate::player::Rank;
+
+/// How one player finished a game that has been archived.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchivedPlayer {
+    pub name: String,
+    pub score: i64,
+    /// 1 for the winner, shared by tied players.
+    pub place: usize,
+    /// The rank earned in each round the player was at the table for.
+    #[serde(default)]
+    pub ranks: Vec<Rank>,
+}
+
+/// A finished game, kept once the next one starts. Players are in finishing order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchivedGame {
+    pub players: Vec<ArchivedPlayer>,
+    pub rounds: u16,
+}
+
+impl ArchivedGame {
+    pub fn new(game: &Game) -> Self {
+        let players = game
+            .standings()
+            .into_iter()
+            .zip(game.get_ranking())
+            .map(|(standing, player)| ArchivedPlayer {
+                name: player.name,
+                score: player.score,
+                place: standing.place,
+                ranks: player.past_ranks.into_iter().chain(player.rank).collect(),
+            })
+            .collect();
+        Self {
+            players,
+            rounds: game.round - 1,
+        }
+    }
+
+    /// Everyone's rank in one round, counting from 0, or `None` for players who hadn't joined
+    /// yet.
+    pub fn round_ranks(&self, round: usize) -> Vec<Option<Rank>> {
+        let rounds = usize::from(self.rounds);
+        self.players
+            .iter()
+            .map(|p| {
+                // Late joiners only have ranks for the last few rounds
+                let skipped = rounds.checked_sub(p.ranks.len())?;
+                p.ranks.get(round.checked_sub(skipped)?).copied()
+            })
+            .collect()
+    }
+
+    pub fn winners(&self) -> Vec<&str> {
+        self.players
+            .iter()
+            .filter(|p| p.place == 1)
+            .map(|p| p.name.as_str())
+            .collect()
+    }
+}
+
+/// One player's record across every archived game.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LeagueStanding {
+    pub name: String,
+    pub games: usize,
+    /// Games won outright or shared.
+    pub wins: usize,
+    pub points: i64,
+    /// The sum of the player's places, for working out their average finish.
+    pub total_places: usize,
+}
+
+impl LeagueStanding {
+    pub fn average_finish(&self) -> f64 {
+        self.total_places as f64 / self.games.max(1) as f64
+    }
+}
+
+/// Games played in one session or league, so scores aren't lost when a new game starts. Players
+/// are matched from game to game by name.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct League {
+    /// Oldest first.
+    pub games: Vec<ArchivedGame>,
+}
+
+impl League {
+    pub fn archive(&mut self, game: &Game) {
+        if game.players.is_empty() {
+            return;
+        }
+        self.games.push(ArchivedGame::new(game));
+    }
+
+    /// Everyone who has played, most wins first, then most points, then best average finish.
+    pub fn standings(&self) -> Vec<LeagueStanding> {
+        let mut standings: Vec<LeagueStanding> = vec![];
+        for player in self.games.iter().flat_map(|g| g.players.iter()) {
+            let i = match standings.iter().position(|s| s.name == player.name) {
+                Some(i) => i,
+                None => {
+                    standings.push(LeagueStanding {
+                        name: player.name.clone(),
+                        games: 0,
+                        wins: 0,
+                        points: 0,
+                        total_places: 0,
+                    });
+                    standings.len() - 1
+                }
+            };
+            let standing = &mut standings[i];
+            standing.games += 1;
+            standing.points += player.score;
+            standing.total_places += player.place;
+            if player.place == 1 {
+                standing.wins += 1;
+            }
+        }
+        standings.sort_by(|a, b| {
+            b.wins.cmp(&a.wins).then(b.points.cmp(&a.points)).then(
+                a.average_finish()
+                    .partial_cmp(&b.average_finish())
+                    .unwrap_or(Ordering::Equal),
+            )
+        });
+        standings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::{GameLength, ScoringTable};
+
+    /// A one round game where the players go out in the order given.
+    fn game_won_by(names: &[&str]) -> Game {
+        let mut game = Game::new(GameLength::Rounds(1), ScoringTable::default());
+        game.set_players(names);
+        for pid in 0..names.len() - 1 {
+            game.handle_go_out(pid);
+        }
+        game.handle_end_round();
+        game
+    }
+
+    #[test]
+    fn archives_in_finishing_order() {
+        let mut league = League::default();
+        league.archive(&game_won_by(&["Alice", "Bob", "Carol"]));
+        let game = &league.games[0];
+        assert_eq!(game.rounds, 1);
+        assert_eq!(game.winners(), vec!["Alice"]);
+        assert_eq!(game.players[2].name, "Carol");
+        assert_eq!(game.players[2].place, 3);
+        assert_eq!(game.players[0].ranks, vec![Rank::Tycoon]);
+        assert_eq!(
+            game.round_ranks(0),
+            vec![Some(Rank::Tycoon), Some(Rank::Commoner), Some(Rank::Beggar)]
+        );
+        league.archive(&Game::default());
+        assert_eq!(league.games.len(), 1);
+    }
+
+    #[test]
+    fn standings_add_up_across_games() {
+        let mut league = League::default();
+        league.archive(&game_won_by(&["Alice", "Bob", "Carol"]));
+        league.archive(&game_won_by(&["Bob", "Alice", "Carol", "Dave"]));
+        league.archive(&game_won_by(&["Bob", "Carol", "Alice"]));
+        let standings = league.standings();
+        let names: Vec<&str> = standings.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["Bob", "Alice", "Carol", "Dave"]);
+        let alice = &standings[1];
+        assert_eq!(alice.games, 3);
+        assert_eq!(alice.wins, 1);
+        // Tycoon, then Rich, then Beggar
+        assert_eq!(alice.points, 50);
+        assert_eq!(alice.average_finish(), 2.0);
+        assert_eq!(standings[3].games, 1);
+    }
+}
@@ -0,0 +1,28 @@
+//! Ranking and scoring rules for the card game Tycoon, with no ties to any particular front end.
+
+pub mod bots;
+pub mod cards;
+pub mod deal;
+pub mod engine;
+pub mod exchange;
+pub mod export;
+pub mod game;
+pub mod league;
+pub mod player;
+pub mod ratings;
+pub mod rules;
+#[cfg(test)]
+mod test_util;
+
+pub use crate::bots::Difficulty;
+pub use crate::cards::{Card, Shuffler, Suit};
+pub use crate::deal::{Deal, Deck};
+pub use crate::engine::{Combo, Play, PlayError, Table};
+pub use crate::export::{GameExport, ImportError};
+pub use crate::game::{
+    check_names, same_name, Game, PlayerOrder, Revolution, RosterSeat, RoundRecord, Standing,
+};
+pub use crate::league::{ArchivedGame, ArchivedPlayer, League, LeagueStanding};
+pub use crate::player::{Player, Rank, RoundResult, MAX_PLAYERS, MIN_PLAYERS};
+pub use crate::ratings::{PlayerRating, RatingBasis, Ratings};
+pub use crate::rules::{Bankruptcy, GameLength, ScoringTable, TieBreak};
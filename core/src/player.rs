@@ -0,0 +1,238 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::bots::Difficulty;
+use crate::rules::ScoringTable;
+
+pub const MIN_PLAYERS: usize = 3;
+pub const MAX_PLAYERS: usize = 8;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Player {
+    pub name: String,
+    pub score: i64,
+    pub rank: Option<Rank>,
+    pub past_ranks: Vec<Rank>,
+    /// Points earned in each round, first round first.
+    #[serde(default)]
+    pub round_points: Vec<i32>,
+    /// How the computer plays this seat, or `None` for a person.
+    #[serde(default)]
+    pub bot: Option<Difficulty>,
+}
+
+/// How one round went for one player.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RoundResult {
+    pub rank: Rank,
+    pub points: i32,
+    /// The player's score after this round.
+    pub total: i64,
+}
+
+/// Ranks are ordered from worst to best, so `Rank::Beggar < Rank::Tycoon`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Serialize, Deserialize)]
+pub enum Rank {
+    Beggar,
+    Poor,
+    Commoner,
+    Rich,
+    Tycoon,
+}
+
+impl fmt::Display for Rank {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Self::Beggar => "Beggar",
+            Self::Poor => "Poor",
+            Self::Commoner => "Commoner",
+            Self::Rich => "Rich",
+            Self::Tycoon => "Tycoon",
+        };
+        f.write_str(name)
+    }
+}
+
+impl Rank {
+    /// The rank earned by finishing in `position` (0 is first out) at a table of `num_players`.
+    /// Three players get Tycoon, Commoner, Beggar; four or more get Tycoon, Rich, then as many
+    /// Commoners as needed, then Poor and Beggar.
+    pub fn for_position(position: usize, num_players: usize) -> Self {
+        if position == 0 {
+            Self::Tycoon
+        } else if position + 1 >= num_players {
+            Self::Beggar
+        } else if num_players < 4 {
+            Self::Commoner
+        } else if position == 1 {
+            Self::Rich
+        } else if position + 2 == num_players {
+            Self::Poor
+        } else {
+            Self::Commoner
+        }
+    }
+}
+
+impl Player {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.into(),
+            score: 0,
+            rank: None,
+            past_ranks: vec![],
+            round_points: vec![],
+            bot: None,
+        }
+    }
+
+    pub fn set_rank(&mut self, rank: Rank) {
+        if let Some(r) = self.rank.take() {
+            self.past_ranks.push(r);
+        }
+        self.rank = Some(rank);
+    }
+
+    pub fn update_score(&mut self, scoring: &ScoringTable) {
+        if let Some(r) = self.rank {
+            self.add_points(scoring.points(r));
+        }
+    }
+
+    /// Records the points for the round just played.
+    pub fn add_points(&mut self, points: i32) {
+        // Saturate rather than overflow so a marathon game can never panic or wrap around
+        self.score = self.score.saturating_add(i64::from(points));
+        self.round_points.push(points);
+    }
+
+    /// Every round played so far, with the points it was worth and the running total. Games
+    /// saved before points were kept per round work them out from `scoring` instead.
+    pub fn round_results(&self, scoring: &ScoringTable) -> Vec<RoundResult> {
+        let ranks: Vec<Rank> = self.past_ranks.iter().copied().chain(self.rank).collect();
+        let recorded = self.round_points.len() == ranks.len();
+        let mut total: i64 = 0;
+        ranks
+            .iter()
+            .enumerate()
+            .map(|(i, rank)| {
+                let points = if recorded {
+                    self.round_points[i]
+                } else {
+                    scoring.points(*rank)
+                };
+                total = total.saturating_add(i64::from(points));
+                RoundResult {
+                    rank: *rank,
+                    points,
+                    total,
+                }
+            })
+            .collect()
+    }
+
+    pub fn is_tycoon(&self) -> bool {
+        matches!(self.rank, Some(Rank::Tycoon))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn play_rounds(player: &mut Player, rank: Rank, rounds: usize, scoring: &ScoringTable) {
+        for _ in 0..rounds {
+            player.set_rank(rank);
+            player.update_score(scoring);
+        }
+    }
+
+    #[test]
+    fn long_game_of_tycoon_wins_adds_up() {
+        let mut player = Player::new("Alice");
+        play_rounds(&mut player, Rank::Tycoon, 100, &ScoringTable::default());
+        assert_eq!(player.score, 3000);
+        assert_eq!(player.past_ranks.len(), 99);
+    }
+
+    #[test]
+    fn score_goes_past_old_u8_limit() {
+        let mut player = Player::new("Alice");
+        play_rounds(&mut player, Rank::Tycoon, 9, &ScoringTable::default());
+        assert_eq!(player.score, 270);
+    }
+
+    #[test]
+    fn mixed_ranks_add_up() {
+        let scoring = ScoringTable::default();
+        let mut player = Player::new("Alice");
+        let ranks = [
+            Rank::Tycoon,
+            Rank::Beggar,
+            Rank::Rich,
+            Rank::Poor,
+            Rank::Commoner,
+        ];
+        for rank in ranks.iter() {
+            play_rounds(&mut player, *rank, 20, &scoring);
+        }
+        assert_eq!(player.score, 20 * (30 + 20 + 10 + 15));
+    }
+
+    #[test]
+    fn negative_scores_accumulate() {
+        let scoring = ScoringTable::preset("Plus/minus (2/1/0/-1)").unwrap();
+        let mut player = Player::new("Alice");
+        play_rounds(&mut player, Rank::Beggar, 500, &scoring);
+        assert_eq!(player.score, -500);
+    }
+
+    #[test]
+    fn round_results_keep_a_running_total() {
+        let scoring = ScoringTable::default();
+        let mut player = Player::new("Alice");
+        play_rounds(&mut player, Rank::Rich, 1, &scoring);
+        play_rounds(&mut player, Rank::Beggar, 1, &scoring);
+        play_rounds(&mut player, Rank::Tycoon, 1, &scoring);
+        let totals: Vec<(Rank, i32, i64)> = player
+            .round_results(&scoring)
+            .iter()
+            .map(|r| (r.rank, r.points, r.total))
+            .collect();
+        assert_eq!(
+            totals,
+            vec![
+                (Rank::Rich, 20, 20),
+                (Rank::Beggar, 0, 20),
+                (Rank::Tycoon, 30, 50)
+            ]
+        );
+    }
+
+    #[test]
+    fn round_results_for_games_saved_without_points() {
+        let scoring = ScoringTable::default();
+        let mut player = Player::new("Alice");
+        player.past_ranks = vec![Rank::Poor];
+        player.rank = Some(Rank::Tycoon);
+        let points: Vec<i32> = player
+            .round_results(&scoring)
+            .iter()
+            .map(|r| r.points)
+            .collect();
+        assert_eq!(points, vec![10, 30]);
+    }
+
+    #[test]
+    fn score_saturates_instead_of_overflowing() {
+        let mut player = Player::new("Alice");
+        player.score = i64::MAX - 10;
+        play_rounds(&mut player, Rank::Tycoon, 1, &ScoringTable::default());
+        assert_eq!(player.score, i64::MAX);
+
+        player.score = i64::MIN + 1;
+        let scoring = ScoringTable::preset("Plus/minus (2/1/0/-1)").unwrap();
+        play_rounds(&mut player, Rank::Beggar, 2, &scoring);
+        assert_eq!(player.score, i64::MIN);
+    }
+}
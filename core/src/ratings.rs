@@ -0,0 +1,271 @@
+//! Elo ratings for multiplayer games, so players who sit at different tables can be compared.
+//! Each result is treated as a head-to-head against everyone else at the table: finishing above
+//! someone is a win, level with them a draw.
+
+use serde::{Deserialize, Serialize};
+
+use crate::league::League;
+use crate::player::Rank;
+
+/// What counts as one result for the ratings.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum RatingBasis {
+    /// Where everyone finished each game.
+    #[default]
+    Games,
+    /// The ranks from every round of every game, which moves ratings faster.
+    Rounds,
+}
+
+impl RatingBasis {
+    pub fn all() -> [Self; 2] {
+        [Self::Games, Self::Rounds]
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Games => "Final standings of each game",
+            Self::Rounds => "Ranks in every round",
+        }
+    }
+
+    /// The value of this choice in the ratings table's drop-down.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::Games => "games",
+            Self::Rounds => "rounds",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::all().iter().copied().find(|b| b.key() == key)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayerRating {
+    pub name: String,
+    pub rating: f64,
+    /// The rating after each result the player took part in, oldest first.
+    pub history: Vec<f64>,
+}
+
+impl PlayerRating {
+    /// How much the last result moved the rating.
+    pub fn last_change(&self) -> f64 {
+        match self.history.as_slice() {
+            [.., before, after] => after - before,
+            [after] => after - Ratings::INITIAL,
+            [] => 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Ratings {
+    /// In the order they first played.
+    pub players: Vec<PlayerRating>,
+}
+
+impl Ratings {
+    /// Everyone's rating before their first result.
+    pub const INITIAL: f64 = 1500.0;
+    /// The most a rating can move in one result.
+    pub const K: f64 = 32.0;
+
+    /// Works the ratings out from scratch, playing through the league's games in order.
+    pub fn from_league(league: &League, basis: RatingBasis) -> Self {
+        let mut ratings = Self::default();
+        for game in league.games.iter() {
+            match basis {
+                RatingBasis::Games => {
+                    let places: Vec<(&str, usize)> = game
+                        .players
+                        .iter()
+                        .map(|p| (p.name.as_str(), p.place))
+                        .collect();
+                    ratings.add_result(&places);
+                }
+                RatingBasis::Rounds => {
+                    for round in 0..usize::from(game.rounds) {
+                        let ranks = game.round_ranks(round);
+                        let places: Vec<(&str, usize)> = game
+                            .players
+                            .iter()
+                            .zip(ranks)
+                            .filter_map(|(p, rank)| Some((p.name.as_str(), place_for(rank?))))
+                            .collect();
+                        ratings.add_result(&places);
+                    }
+                }
+            }
+        }
+        ratings
+    }
+
+    pub fn get(&self, name: &str) -> Option<&PlayerRating> {
+        self.players.iter().find(|p| p.name == name)
+    }
+
+    fn rating(&self, name: &str) -> f64 {
+        self.get(name).map_or(Self::INITIAL, |p| p.rating)
+    }
+
+    /// Updates the ratings from one result, given as each player's name and place, where a lower
+    /// place is better and players sharing a place drew.
+    pub fn add_result(&mut self, places: &[(&str, usize)]) {
+        if places.len() < 2 {
+            return;
+        }
+        let before: Vec<f64> = places.iter().map(|(name, _)| self.rating(name)).collect();
+        let opponents = (places.len() - 1) as f64;
+        for (i, (name, place)) in places.iter().enumerate() {
+            let mut change = 0.0;
+            for (j, (_, other)) in places.iter().enumerate() {
+                if i == j {
+                    continue;
+                }
+                let expected = 1.0 / (1.0 + 10f64.powf((before[j] - before[i]) / 400.0));
+                let actual = if place < other {
+                    1.0
+                } else if place == other {
+                    0.5
+                } else {
+                    0.0
+                };
+                change += actual - expected;
+            }
+            let rating = before[i] + Self::K * change / opponents;
+            match self.players.iter_mut().find(|p| p.name == *name) {
+                Some(player) => {
+                    player.rating = rating;
+                    player.history.push(rating);
+                }
+                None => self.players.push(PlayerRating {
+                    name: name.to_string(),
+                    rating,
+                    history: vec![rating],
+                }),
+            }
+        }
+    }
+
+    /// Highest rating first.
+    pub fn leaderboard(&self) -> Vec<&PlayerRating> {
+        let mut players: Vec<&PlayerRating> = self.players.iter().collect();
+        players.sort_by(|a, b| b.rating.total_cmp(&a.rating));
+        players
+    }
+}
+
+/// Ranks as places for one round: the Tycoon first and the Beggar last, with Commoners level.
+fn place_for(rank: Rank) -> usize {
+    match rank {
+        Rank::Tycoon => 1,
+        Rank::Rich => 2,
+        Rank::Commoner => 3,
+        Rank::Poor => 4,
+        Rank::Beggar => 5,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{Game, RosterSeat};
+    use crate::rules::{GameLength, ScoringTable};
+    use crate::test_util::play_round;
+
+    /// A game where the players go out in the order given each round.
+    fn game(names: &[&str], rounds: &[&[usize]]) -> Game {
+        let mut game = Game::new(
+            GameLength::Rounds(rounds.len() as u8),
+            ScoringTable::default(),
+        );
+        game.set_players(names);
+        for order in rounds.iter() {
+            play_round(&mut game, order);
+        }
+        game
+    }
+
+    #[test]
+    fn winners_gain_what_losers_lose() {
+        let mut ratings = Ratings::default();
+        ratings.add_result(&[("A", 1), ("B", 2), ("C", 3)]);
+        let a = ratings.get("A").unwrap();
+        let b = ratings.get("B").unwrap();
+        let c = ratings.get("C").unwrap();
+        assert_eq!(a.rating, 1516.0);
+        assert_eq!(b.rating, 1500.0);
+        assert_eq!(c.rating, 1484.0);
+        assert_eq!(a.last_change(), 16.0);
+
+        ratings.add_result(&[("A", 1), ("D", 1)]);
+        let a = ratings.get("A").unwrap();
+        let d = ratings.get("D").unwrap();
+        // A was the favourite, so a draw costs them
+        assert!(a.rating < 1516.0);
+        assert!((a.rating + d.rating - 3016.0).abs() < 1e-9);
+        assert_eq!(a.history.len(), 2);
+    }
+
+    #[test]
+    fn ratings_from_a_league() {
+        let mut league = League::default();
+        league.archive(&game(&["A", "B", "C"], &[&[0, 1], &[2, 1]]));
+        league.archive(&game(&["C", "A", "D"], &[&[2, 1]]));
+
+        let ratings = Ratings::from_league(&league, RatingBasis::Games);
+        assert_eq!(ratings.get("A").unwrap().history.len(), 2);
+        assert_eq!(ratings.get("D").unwrap().history.len(), 1);
+        let names: Vec<&str> = ratings
+            .leaderboard()
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect();
+        assert_eq!(names.len(), 4);
+        assert_eq!(names[0], "D");
+
+        let ratings = Ratings::from_league(&league, RatingBasis::Rounds);
+        assert_eq!(ratings.get("A").unwrap().history.len(), 3);
+        assert_eq!(ratings.get("B").unwrap().history.len(), 2);
+    }
+
+    #[test]
+    fn late_joiners_are_rated_from_when_they_sat_down() {
+        let mut first = game(&["A", "B", "C"], &[&[0, 1]]);
+        first.game_length = GameLength::Rounds(2);
+        first.edit_roster(&[
+            RosterSeat {
+                name: "A".into(),
+                pid: Some(0),
+                bot: None,
+            },
+            RosterSeat {
+                name: "B".into(),
+                pid: Some(1),
+                bot: None,
+            },
+            RosterSeat {
+                name: "C".into(),
+                pid: Some(2),
+                bot: None,
+            },
+            RosterSeat {
+                name: "E".into(),
+                pid: None,
+                bot: None,
+            },
+        ]);
+        for pid in [3, 0, 1].iter() {
+            first.handle_go_out(*pid);
+        }
+        first.handle_end_round();
+        let mut league = League::default();
+        league.archive(&first);
+        let ratings = Ratings::from_league(&league, RatingBasis::Rounds);
+        assert_eq!(ratings.get("A").unwrap().history.len(), 2);
+        assert_eq!(ratings.get("E").unwrap().history.len(), 1);
+    }
+}
@@ -0,0 +1,276 @@
+use serde::{Deserialize, Serialize};
+
+use crate::player::Rank;
+
+/// How long a game lasts, chosen during setup.
+#[derive(Debug, Clone, PartialEq, Copy, Serialize, Deserialize)]
+pub enum GameLength {
+    /// Play a fixed number of rounds.
+    Rounds(u8),
+    /// Play until someone's score reaches the target.
+    Points(u16),
+}
+
+impl Default for GameLength {
+    fn default() -> Self {
+        Self::Rounds(3)
+    }
+}
+
+impl GameLength {
+    pub fn is_over(&self, rounds_played: u16, top_score: i64) -> bool {
+        match self {
+            Self::Rounds(n) => rounds_played >= u16::from(*n),
+            Self::Points(target) => top_score >= i64::from(*target),
+        }
+    }
+
+    pub fn round_label(&self, round: u16) -> String {
+        match self {
+            Self::Rounds(n) => format!("Round {} of {}", round, n),
+            Self::Points(target) => format!("Round {} (first to {} points)", round, target),
+        }
+    }
+
+    /// The kind of length as used by the setup form.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Rounds(_) => "rounds",
+            Self::Points(_) => "points",
+        }
+    }
+
+    pub fn value(&self) -> u16 {
+        match self {
+            Self::Rounds(n) => u16::from(*n),
+            Self::Points(target) => *target,
+        }
+    }
+
+    pub fn with_kind(&self, kind: &str) -> Self {
+        match (kind, self) {
+            ("rounds", Self::Rounds(_)) | ("points", Self::Points(_)) => *self,
+            ("points", _) => Self::Points(100),
+            _ => Self::Rounds(3),
+        }
+    }
+
+    /// Replaces the number of rounds or target score, ignoring anything that isn't a positive
+    /// number.
+    pub fn with_value(&self, value: &str) -> Self {
+        match self {
+            Self::Rounds(_) => match value.trim().parse::<u8>() {
+                Ok(n) if n > 0 => Self::Rounds(n),
+                _ => *self,
+            },
+            Self::Points(_) => match value.trim().parse::<u16>() {
+                Ok(n) if n > 0 => Self::Points(n),
+                _ => *self,
+            },
+        }
+    }
+}
+
+/// What happens to the reigning Tycoon when someone else goes out first (miyako-ochi), chosen
+/// during setup.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum Bankruptcy {
+    /// Nothing: the Tycoon plays on and finishes wherever they go out.
+    Off,
+    /// The Tycoon becomes the Beggar straight away.
+    #[default]
+    Beggar,
+    /// The Tycoon drops out of the round. They're ranked Beggar for the card exchange but score
+    /// nothing, and everyone else earns their rank as usual.
+    DropOut,
+}
+
+impl Bankruptcy {
+    pub fn all() -> [Self; 3] {
+        [Self::Off, Self::Beggar, Self::DropOut]
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Off => "No bankruptcy",
+            Self::Beggar => "Tycoon becomes the Beggar",
+            Self::DropOut => "Tycoon drops out of the round",
+        }
+    }
+
+    /// How this rule is written in transcripts, simulator options and the setup form.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Beggar => "beggar",
+            Self::DropOut => "drop-out",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::all().iter().copied().find(|b| b.key() == key)
+    }
+}
+
+/// How players on the same score are placed in the final standings, chosen during setup.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum TieBreak {
+    /// Whoever was Tycoon in the most rounds goes ahead.
+    MostTycoons,
+    /// Whoever had the better rank in the last round goes ahead.
+    LastRound,
+    /// Nobody goes ahead, and the players share the placing.
+    #[default]
+    Shared,
+}
+
+impl TieBreak {
+    pub fn all() -> [Self; 3] {
+        [Self::MostTycoons, Self::LastRound, Self::Shared]
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::MostTycoons => "Most Tycoon finishes",
+            Self::LastRound => "Best last-round rank",
+            Self::Shared => "Shared placing",
+        }
+    }
+
+    /// How this rule is written in a transcript's `tie-break:` line and the setup form.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::MostTycoons => "tycoons",
+            Self::LastRound => "last-round",
+            Self::Shared => "shared",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::all().iter().copied().find(|t| t.key() == key)
+    }
+}
+
+/// Points awarded for each rank at the end of a round, chosen during setup.
+#[derive(Debug, Clone, PartialEq, Copy, Serialize, Deserialize)]
+pub struct ScoringTable {
+    pub tycoon: i32,
+    pub rich: i32,
+    pub commoner: i32,
+    pub poor: i32,
+    pub beggar: i32,
+}
+
+impl Default for ScoringTable {
+    fn default() -> Self {
+        Self::presets()[0].1
+    }
+}
+
+impl ScoringTable {
+    /// House rules we know about. The first one is the default.
+    pub fn presets() -> Vec<(&'static str, ScoringTable)> {
+        vec![
+            (
+                "Classic (30/20/10/0)",
+                Self {
+                    tycoon: 30,
+                    rich: 20,
+                    commoner: 15,
+                    poor: 10,
+                    beggar: 0,
+                },
+            ),
+            (
+                "Plus/minus (2/1/0/-1)",
+                Self {
+                    tycoon: 2,
+                    rich: 1,
+                    commoner: 0,
+                    poor: 0,
+                    beggar: -1,
+                },
+            ),
+            (
+                "Daifugō (3/2/1/0)",
+                Self {
+                    tycoon: 3,
+                    rich: 2,
+                    commoner: 1,
+                    poor: 1,
+                    beggar: 0,
+                },
+            ),
+        ]
+    }
+
+    pub fn preset(name: &str) -> Option<Self> {
+        Self::presets()
+            .into_iter()
+            .find(|(preset_name, _)| *preset_name == name)
+            .map(|(_, table)| table)
+    }
+
+    /// Reads points written as text: either the start of a preset's name, or points for
+    /// Tycoon/Rich/Commoner/Poor/Beggar like `30/20/15/10/0`.
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        // Every preset name starts with nothing at all
+        if value.is_empty() {
+            return None;
+        }
+        if let Some(table) = Self::presets()
+            .into_iter()
+            .find(|(name, _)| name.to_lowercase().starts_with(&value.to_lowercase()))
+            .map(|(_, table)| table)
+        {
+            return Some(table);
+        }
+        let points: Result<Vec<i32>, _> = value.split('/').map(|p| p.trim().parse()).collect();
+        match points.as_deref() {
+            Ok([tycoon, rich, commoner, poor, beggar]) => Some(Self {
+                tycoon: *tycoon,
+                rich: *rich,
+                commoner: *commoner,
+                poor: *poor,
+                beggar: *beggar,
+            }),
+            _ => None,
+        }
+    }
+
+    /// The name of the preset these points match, if any.
+    pub fn preset_name(&self) -> Option<&'static str> {
+        Self::presets()
+            .into_iter()
+            .find(|(_, table)| table == self)
+            .map(|(name, _)| name)
+    }
+
+    pub fn points(&self, rank: Rank) -> i32 {
+        match rank {
+            Rank::Tycoon => self.tycoon,
+            Rank::Rich => self.rich,
+            Rank::Commoner => self.commoner,
+            Rank::Poor => self.poor,
+            Rank::Beggar => self.beggar,
+        }
+    }
+
+    /// Replaces the points for one rank, ignoring anything that isn't a whole number.
+    pub fn with_points(&self, rank: Rank, value: &str) -> Self {
+        let points = match value.trim().parse::<i32>() {
+            Ok(points) => points,
+            Err(_) => return *self,
+        };
+        let mut table = *self;
+        match rank {
+            Rank::Tycoon => table.tycoon = points,
+            Rank::Rich => table.rich = points,
+            Rank::Commoner => table.commoner = points,
+            Rank::Poor => table.poor = points,
+            Rank::Beggar => table.beggar = points,
+        }
+        table
+    }
+}
@@ -0,0 +1,41 @@
+//! Helpers shared by the tests in more than one module.
+
+use crate::game::Game;
+use crate::player::{Player, Rank};
+
+/// A game with players named P0, P1 and so on.
+pub fn game_with(num_players: usize) -> Game {
+    let names: Vec<String> = (0..num_players).map(|i| format!("P{}", i)).collect();
+    let mut game = Game::default();
+    game.set_players(&names);
+    game
+}
+
+/// Confirms every card exchange and starts the round.
+pub fn finish_exchange(game: &mut Game) {
+    game.confirm_all_exchanges();
+    game.start_round();
+}
+
+/// Sends players out in `order`, ends the round, and swaps cards for the next one.
+pub fn play_round(game: &mut Game, order: &[usize]) {
+    for pid in order {
+        game.handle_go_out(*pid);
+    }
+    assert!(game.is_round_complete());
+    game.handle_end_round();
+    finish_exchange(game);
+}
+
+/// Players named P0, P1 and so on, holding the ranks given.
+pub fn ranked_players(ranks: &[Rank]) -> Vec<Player> {
+    ranks
+        .iter()
+        .enumerate()
+        .map(|(i, rank)| {
+            let mut player = Player::new(&format!("P{}", i));
+            player.rank = Some(*rank);
+            player
+        })
+        .collect()
+}
@@ -1,7 +1,11 @@
+use crate::download;
 use crate::msg::{Msg, SetupState};
-use crate::player::{Player, Rank};
 use seed::{prelude::*, *};
-use std::collections::HashMap;
+use tycoon_core::exchange::{self, Exchange, Instruction};
+use tycoon_core::{
+    Bankruptcy, Deal, Deck, Difficulty, Game, GameLength, League, Player, PlayerOrder, Rank,
+    RatingBasis, Ratings, RosterSeat, RoundResult, ScoringTable, Standing, Table, TieBreak,
+};
 
 pub fn header() -> Node<Msg> {
     header![
@@ -13,6 +17,137 @@ pub fn header() -> Node<Msg> {
     ]
 }
 
+pub fn resume_prompt(round: u16, num_players: usize) -> Node<Msg> {
+    div![
+        class![
+            "flex",
+            "w-full",
+            "max-w-3xl",
+            "mx-auto",
+            "mt-4",
+            "px-4",
+            "py-2",
+            "items-center",
+            "justify-center",
+            "bg-indigo-100",
+            "border",
+            "border-indigo-600",
+            "rounded-sm"
+        ],
+        span![
+            class!["font-bold", "mr-2"],
+            format!(
+                "Resume the saved {} player game from round {}?",
+                num_players, round
+            ),
+        ],
+        button![
+            class![
+                "px-4",
+                "py-2",
+                "bg-green-600",
+                "hover:shadow",
+                "hover:bg-green-800",
+                "text-white",
+                "rounded-full",
+                "mx-2"
+            ],
+            simple_ev(Ev::Click, Msg::ResumeGame),
+            "Resume"
+        ],
+        button![
+            class![
+                "px-4",
+                "py-2",
+                "bg-red-600",
+                "hover:shadow",
+                "hover:bg-red-800",
+                "text-white",
+                "rounded-full",
+                "mx-2"
+            ],
+            simple_ev(Ev::Click, Msg::DiscardSavedGame),
+            "Discard"
+        ],
+    ]
+}
+
+pub fn import_button() -> Node<Msg> {
+    label![
+        class![
+            "px-4",
+            "py-2",
+            "bg-green-600",
+            "hover:shadow",
+            "hover:bg-green-800",
+            "text-white",
+            "rounded-full",
+            "mx-2",
+            "cursor-pointer"
+        ],
+        "Import Game",
+        input![
+            class!["hidden"],
+            attrs! {
+                At::Type => "file",
+                At::Accept => ".json,application/json",
+            },
+            ev(Ev::Change, |event| {
+                let input = event
+                    .target()?
+                    .dyn_into::<web_sys::HtmlInputElement>()
+                    .ok()?;
+                let file = input.files()?.get(0)?;
+                // Clear the input so picking the same file again still counts as a change
+                input.set_value("");
+                Some(Msg::ImportFile(file))
+            }),
+        ],
+    ]
+}
+
+fn history_button(text: &str, enabled: bool, msg: Msg) -> Node<Msg> {
+    button![
+        class![
+            "px-3",
+            "py-1",
+            "rounded-full",
+            "text-white",
+            "mx-2",
+            "hover:shadow" => enabled,
+            "bg-indigo-600" => enabled,
+            "hover:bg-indigo-800" => enabled,
+            "bg-gray-600" => !enabled,
+        ],
+        attrs! {At::Disabled => (!enabled).as_at_value()},
+        simple_ev(Ev::Click, msg),
+        text
+    ]
+}
+
+pub fn history_controls(can_undo: bool, can_redo: bool) -> Node<Msg> {
+    div![
+        class!["flex", "w-full", "justify-center", "mt-2"],
+        history_button("Undo", can_undo, Msg::Undo),
+        history_button("Redo", can_redo, Msg::Redo),
+    ]
+}
+
+pub fn round_display(round: u16, game_length: &GameLength) -> Node<Msg> {
+    h2![
+        class![
+            "flex",
+            "w-full",
+            "justify-center",
+            "mt-2",
+            "text-xl",
+            "font-bold",
+            "text-indigo-700"
+        ],
+        game_length.round_label(round)
+    ]
+}
+
 fn table_heading(text: &str) -> Node<Msg> {
     th![
         class![
@@ -36,7 +171,7 @@ fn rank_string(rank: &Option<Rank>) -> String {
     }
 }
 
-fn player_row(id: &usize, player: &Player, already_out: bool) -> Node<Msg> {
+fn player_row(id: &usize, player: &Player, already_out: bool, foul_finish: bool) -> Node<Msg> {
     tr![
         class!["my-2"],
         td![
@@ -79,14 +214,213 @@ fn player_row(id: &usize, player: &Player, already_out: bool) -> Node<Msg> {
                 "Go Out",
                 simple_ev(Ev::Click, Msg::GoOut(*id)),
             ]
-        ]
+        ],
+        td![
+            class!["h-10", "pl-2"],
+            button![
+                class! [
+                   "px-2",
+                   "py-1",
+                   "rounded-sm",
+                   "text-white",
+                   "hover:shadow" => !already_out,
+                   "bg-red-600" => !already_out,
+                   "hover:bg-red-800" => !already_out,
+                   "bg-gray-600" => already_out,
+                ],
+                attrs! {At::Disabled => already_out.as_at_value()},
+                "Revolution",
+                simple_ev(Ev::Click, Msg::Revolution(*id)),
+            ]
+        ],
+        if foul_finish {
+            td![
+                class!["h-10", "pl-2"],
+                button![
+                    class! [
+                       "px-2",
+                       "py-1",
+                       "rounded-sm",
+                       "text-white",
+                       "hover:shadow" => !already_out,
+                       "bg-yellow-600" => !already_out,
+                       "hover:bg-yellow-800" => !already_out,
+                       "bg-gray-600" => already_out,
+                    ],
+                    attrs! {
+                        At::Disabled => already_out.as_at_value(),
+                        At::Title => "Went out on a Joker, a 2, an 8 or the 3 of spades",
+                    },
+                    "Foul",
+                    simple_ev(Ev::Click, Msg::FoulFinish(*id)),
+                ]
+            ]
+        } else {
+            empty![]
+        }
+    ]
+}
+
+/// The revolutions so far this round, and which way round the cards are now.
+pub fn revolution_status(game: &Game) -> Node<Msg> {
+    if game.revolutions.is_empty() {
+        return empty![];
+    }
+    let names: Vec<&str> = game
+        .revolutions
+        .iter()
+        .filter_map(|r| game.players.get(r.pid))
+        .map(|p| p.name.as_str())
+        .collect();
+    div![
+        class!["flex", "flex-col", "w-full", "items-center", "mt-2"],
+        p![
+            class!["font-bold", "text-red-700" => game.is_reversed()],
+            if game.is_reversed() {
+                "Card strength is reversed: 3s are high and 2s are low."
+            } else {
+                "Card strength is back to normal."
+            }
+        ],
+        p![
+            class!["text-sm", "text-gray-700"],
+            format!("Revolutions this round: {}", names.join(", "))
+        ],
+    ]
+}
+
+fn card_button(index: usize, card: String, selected: bool, enabled: bool) -> Node<Msg> {
+    button![
+        class![
+            "px-2",
+            "py-1",
+            "m-1",
+            "rounded-sm",
+            "border",
+            "border-indigo-600",
+            "bg-indigo-200" => selected,
+            "bg-white" => !selected,
+            "hover:shadow" => enabled,
+        ],
+        attrs! {At::Disabled => (!enabled).as_at_value()},
+        card,
+        simple_ev(Ev::Click, Msg::SelectCard(index)),
     ]
 }
 
-pub fn score_table(
-    players: &HashMap<usize, Player>,
-    players_out: &HashMap<usize, Rank>,
+/// The round being played out against computer players: the pile, how many cards everyone has
+/// left, and the hand of the person whose turn it is.
+pub fn practice_table(
+    game: &Game,
+    table: &Table,
+    selected: &[usize],
+    error: &Option<String>,
 ) -> Node<Msg> {
+    let name = |pid: usize| game.players.get(pid).map_or("", |p| p.name.as_str());
+    // Show whoever's turn it is if they're a person, otherwise the first person at the table
+    let viewer = if game.players[table.turn].bot.is_none() {
+        Some(table.turn)
+    } else {
+        game.players.iter().position(|p| p.bot.is_none())
+    };
+    let my_turn = !table.is_over() && !game.exchanging && viewer == Some(table.turn);
+    div![
+        class![
+            "flex",
+            "flex-col",
+            "w-full",
+            "items-center",
+            "mt-4",
+            "mx-auto",
+            "max-w-2xl"
+        ],
+        h2![class!["font-bold", "text-xl", "text-indigo-700"], "Table"],
+        p![match table.pile.last() {
+            Some((pid, play)) => format!("On the pile: {} from {}", play, name(*pid)),
+            None => "The pile is empty.".into(),
+        }],
+        ul![
+            class!["text-sm", "text-gray-700"],
+            table.hands.iter().enumerate().map(|(pid, hand)| li![
+                class!["font-bold" => pid == table.turn && !table.is_over()],
+                format!(
+                    "{}: {} {}{}",
+                    name(pid),
+                    hand.len(),
+                    if hand.len() == 1 { "card" } else { "cards" },
+                    if table.out[pid] {
+                        " (out)"
+                    } else if table.passed[pid] {
+                        " (passed)"
+                    } else {
+                        ""
+                    }
+                )
+            ]),
+        ],
+        match viewer {
+            Some(pid) => div![
+                class!["flex", "flex-col", "items-center", "mt-2"],
+                p![
+                    class!["font-bold"],
+                    if my_turn {
+                        format!("{}, it's your turn", name(pid))
+                    } else {
+                        format!("{}'s hand", name(pid))
+                    }
+                ],
+                div![
+                    class!["flex", "flex-wrap", "justify-center"],
+                    table.hands[pid]
+                        .iter()
+                        .enumerate()
+                        .map(|(i, card)| card_button(
+                            i,
+                            card.to_string(),
+                            selected.contains(&i),
+                            my_turn
+                        )),
+                ],
+                div![
+                    class!["flex", "mt-2"],
+                    history_button("Play", my_turn && !selected.is_empty(), Msg::PlayCards),
+                    history_button("Pass", my_turn && table.top().is_some(), Msg::PassTurn),
+                ],
+            ],
+            None => empty![],
+        },
+        error
+            .as_ref()
+            .map(|e| p![class!["text-red-700", "font-bold", "mt-2"], e]),
+    ]
+}
+
+pub fn sort_controls(current: PlayerOrder) -> Node<Msg> {
+    div![
+        class!["flex", "w-full", "justify-center", "items-center", "mt-2"],
+        span![class!["text-indigo-700", "font-bold", "mr-2"], "Sort by"],
+        PlayerOrder::all().iter().map(|order| {
+            let selected = *order == current;
+            button![
+                class![
+                    "px-3",
+                    "py-1",
+                    "rounded-full",
+                    "mx-1",
+                    "text-white" => selected,
+                    "bg-indigo-600" => selected,
+                    "text-indigo-700" => !selected,
+                    "bg-indigo-100" => !selected,
+                    "hover:shadow" => !selected,
+                ],
+                order.label(),
+                simple_ev(Ev::Click, Msg::SortPlayers(*order)),
+            ]
+        }),
+    ]
+}
+
+pub fn score_table(game: &Game, order: PlayerOrder) -> Node<Msg> {
     table![
         class!["table-auto", "border-collapse", "mt-2"],
         thead![tr![
@@ -94,77 +428,354 @@ pub fn score_table(
             table_heading("Score"),
             table_heading("Rank"),
             table_heading(""),
+            table_heading(""),
+            if game.foul_finish {
+                table_heading("")
+            } else {
+                empty![]
+            },
         ],],
         tbody![
             class!["px-4", "pt-2", "text-lg"],
-            players
-                .iter()
-                .map(|(i, player)| player_row(i, player, players_out.contains_key(i))),
+            game.player_order(order).iter().map(|pid| player_row(
+                pid,
+                &game.players[*pid],
+                // Nobody goes out until the cards have been swapped, and with bots at the table
+                // the cards decide who goes out
+                game.players_out.contains_key(pid) || game.exchanging || game.has_bots(),
+                game.foul_finish
+            )),
         ],
     ]
 }
 
-pub fn player_instructions(players: &HashMap<usize, Player>) -> Node<Msg> {
-    let mut tycoon = Player::new("Tycoon");
-    let mut rich = Player::new("Rich");
-    let mut poor = Player::new("Poor");
-    let mut beggar = Player::new("Beggar");
+fn history_cell(result: Option<&RoundResult>) -> Node<Msg> {
+    td![
+        class!["px-2", "py-1", "text-center", "border-b", "border-gray-300"],
+        match result {
+            Some(result) => nodes![
+                div![result.rank.to_string()],
+                div![
+                    class!["text-sm", "text-gray-700"],
+                    format!("{:+} ({})", result.points, result.total)
+                ],
+            ],
+            None => nodes![plain!["-"]],
+        }
+    ]
+}
 
-    for (_, player) in players.iter() {
-        match player.rank {
-            Some(Rank::Tycoon) => tycoon = player.clone(),
-            Some(Rank::Rich) => rich = player.clone(),
-            Some(Rank::Poor) => poor = player.clone(),
-            Some(Rank::Beggar) => beggar = player.clone(),
-            None => (),
-        };
+/// Cumulative standings for every game finished this session, with the latest winners.
+pub fn league_table(league: &League) -> Node<Msg> {
+    if league.games.is_empty() {
+        return empty![];
     }
+    let cell = |text: String| {
+        td![
+            class!["px-2", "py-1", "text-center", "border-b", "border-gray-300"],
+            text
+        ]
+    };
+    div![
+        class![
+            "flex",
+            "flex-col",
+            "w-full",
+            "items-center",
+            "mt-4",
+            "mx-auto",
+            "max-w-2xl"
+        ],
+        h2![class!["font-bold", "text-xl", "text-indigo-700"], "League"],
+        p![
+            class!["text-sm", "text-gray-700"],
+            match league.games.len() {
+                1 => "1 game played".to_string(),
+                n => format!("{} games played", n),
+            },
+            league
+                .games
+                .last()
+                .map(|game| format!(", last won by {}", game.winners().join(" & "))),
+        ],
+        table![
+            class!["table-auto", "border-collapse", "mt-2"],
+            thead![tr![
+                table_heading("Player"),
+                table_heading("Games"),
+                table_heading("Wins"),
+                table_heading("Points"),
+                table_heading("Avg Finish"),
+            ]],
+            tbody![league.standings().into_iter().map(|s| tr![
+                td![
+                    class!["px-2", "py-1", "font-bold", "border-b", "border-gray-300"],
+                    s.name.as_str()
+                ],
+                cell(s.games.to_string()),
+                cell(s.wins.to_string()),
+                cell(s.points.to_string()),
+                cell(format!("{:.1}", s.average_finish())),
+            ])],
+        ],
+        history_button("Clear League", true, Msg::ClearLeague),
+    ]
+}
 
-    ul![
-        class!["text-xl", "mx-4"],
-        li![
-            span![
-                class!["text-indigo-600", "font-bold"],
-                format!("{}: ", tycoon.name),
-            ],
-            "passes ",
-            em!["two cards "],
-            "of their choice to ",
-            strong![beggar.name.as_str(),],
-            ", and receives no extra cards.",
+/// Everyone's rating from the games this session, best first, with how it got there.
+pub fn ratings_table(league: &League, basis: RatingBasis) -> Node<Msg> {
+    if league.games.is_empty() {
+        return empty![];
+    }
+    let ratings = Ratings::from_league(league, basis);
+    let cell = |text: String| {
+        td![
+            class!["px-2", "py-1", "text-center", "border-b", "border-gray-300"],
+            text
+        ]
+    };
+    div![
+        class![
+            "flex",
+            "flex-col",
+            "w-full",
+            "items-center",
+            "mt-4",
+            "mx-auto",
+            "max-w-2xl"
         ],
-        li![
-            span![
-                class!["text-indigo-600", "font-bold"],
-                format!("{}: ", rich.name),
+        h2![class!["font-bold", "text-xl", "text-indigo-700"], "Ratings"],
+        rule_select(
+            "Rate by",
+            RatingBasis::all()
+                .iter()
+                .map(|b| (b.key(), b.label(), *b == basis))
+                .collect(),
+            Msg::SetRatingBasis,
+        ),
+        table![
+            class!["table-auto", "border-collapse", "mt-2"],
+            thead![tr![
+                table_heading("Player"),
+                table_heading("Rating"),
+                table_heading("Last"),
+                table_heading("History"),
+            ]],
+            tbody![ratings.leaderboard().into_iter().map(|p| {
+                let mut history: Vec<String> = std::iter::once(Ratings::INITIAL)
+                    .chain(p.history.iter().copied())
+                    .map(|r| format!("{:.0}", r))
+                    .collect();
+                // Rating by rounds piles up a long history, so only show the latest
+                if history.len() > 10 {
+                    history.drain(..history.len() - 9);
+                    history.insert(0, "…".into());
+                }
+                tr![
+                    td![
+                        class!["px-2", "py-1", "font-bold", "border-b", "border-gray-300"],
+                        p.name.as_str()
+                    ],
+                    cell(format!("{:.0}", p.rating)),
+                    cell(format!("{:+.1}", p.last_change())),
+                    td![
+                        class![
+                            "px-2",
+                            "py-1",
+                            "text-sm",
+                            "text-gray-700",
+                            "border-b",
+                            "border-gray-300"
+                        ],
+                        history.join(" → ")
+                    ],
+                ]
+            })],
+        ],
+    ]
+}
+
+/// Every player's rank and points round by round, with the running total in brackets.
+pub fn score_history(game: &Game, order: PlayerOrder) -> Node<Msg> {
+    let results: Vec<(&Player, Vec<RoundResult>)> = game
+        .player_order(order)
+        .into_iter()
+        .map(|pid| {
+            let player = &game.players[pid];
+            (player, player.round_results(&game.scoring))
+        })
+        .collect();
+    let rounds = results.iter().map(|(_, r)| r.len()).max().unwrap_or(0);
+    if rounds == 0 {
+        return empty![];
+    }
+    div![
+        class![
+            "flex",
+            "w-full",
+            "justify-center",
+            "mt-4",
+            "overflow-x-auto"
+        ],
+        table![
+            class!["table-auto", "border-collapse"],
+            thead![tr![
+                table_heading("Player Name"),
+                (1..=rounds).map(|round| table_heading(&format!("R{}", round))),
+                table_heading("Total"),
+            ]],
+            tbody![results.iter().map(|(player, results)| {
+                // Players who joined partway through have nothing for the early rounds
+                let joined = rounds - results.len();
+                tr![
+                    td![class!["pl-4", "pr-2", "font-bold"], player.name.as_str()],
+                    (0..rounds).map(|round| history_cell(
+                        round.checked_sub(joined).and_then(|r| results.get(r))
+                    )),
+                    td![
+                        class!["px-2", "text-center", "font-bold"],
+                        player.score.to_string()
+                    ],
+                ]
+            })],
+        ]
+    ]
+}
+
+fn instruction_item(instruction: &Instruction, players: &[Player], deal: &Deal) -> Node<Msg> {
+    let name = |pid: usize| match players.get(pid) {
+        Some(p) => p.name.as_str(),
+        None => "",
+    };
+    li![
+        span![
+            class!["text-indigo-600", "font-bold"],
+            format!("{}: ", name(instruction.pid)),
+        ],
+        match &instruction.pass {
+            Some(pass) if pass.highest => nodes![
+                plain!["passes their "],
+                em![format!("{} ", pass.cards_text())],
+                plain!["to "],
+                strong![name(pass.to)],
             ],
-            "passes ",
-            em!["one card "],
-            "of their choice to ",
-            strong![poor.name.as_str()],
-            ", and receives the third extra card (if applicable)",
+            Some(pass) => nodes![
+                plain!["passes "],
+                em![format!("{} ", pass.cards_text())],
+                plain!["of their choice to "],
+                strong![name(pass.to)],
+            ],
+            None => nodes![plain!["exchanges "], em!["no cards"]],
+        },
+        format!(", and {}", deal.extra_card_text(instruction.pid)),
+    ]
+}
+
+/// How to deal this round, and after the first round who passes what to whom.
+pub fn player_instructions(game: &Game) -> Node<Msg> {
+    let players = &game.players;
+    let deal = game.deal();
+    div![
+        p![
+            class!["text-lg", "mx-4", "mb-2", "text-gray-700"],
+            deal.describe(players)
         ],
-        li![
-            span![
-                class!["text-indigo-600", "font-bold"],
-                format!("{}: ", poor.name),
+        ul![
+            class!["text-xl", "mx-4"],
+            exchange::instructions(players)
+                .iter()
+                .map(|instruction| instruction_item(instruction, players, &deal)),
+        ],
+    ]
+}
+
+fn exchange_cards_input(index: usize, upper: bool, name: &str, cards: &str) -> Node<Msg> {
+    input![
+        class![
+            "bg-indigo-100",
+            "border-indigo-500",
+            "border",
+            "rounded",
+            "px-2",
+            "py-1",
+            "mx-1",
+            "w-40"
+        ],
+        attrs! {
+            At::Value => cards,
+            At::Placeholder => format!("Cards {} passed", name),
+        },
+        input_ev(Ev::Input, move |value| Msg::SetExchangeCards(
+            index, upper, value
+        )),
+    ]
+}
+
+fn exchange_row(index: usize, exchange: &Exchange, players: &[Player]) -> Node<Msg> {
+    let name = |pid: usize| match players.get(pid) {
+        Some(p) => p.name.as_str(),
+        None => "",
+    };
+    let done = exchange.done;
+    li![
+        class!["flex", "flex-wrap", "items-center", "my-1"],
+        label![
+            class!["flex", "items-center", "mr-2", "line-through" => done],
+            input![
+                class!["mr-2"],
+                attrs! {
+                    At::Type => "checkbox",
+                    At::Checked => done.as_at_value(),
+                },
+                ev(Ev::Change, move |_| Msg::ConfirmExchange(index, !done)),
             ],
-            "passes their ",
-            em!["highest card "],
-            "to ",
-            strong![rich.name.as_str()],
-            ", and receives the second extra card (if applicable)",
+            exchange.describe(players),
         ],
-        li![
-            span![
-                class!["text-indigo-600", "font-bold"],
-                format!("{}: ", beggar.name),
+        exchange_cards_input(index, true, name(exchange.upper), &exchange.upper_cards),
+        exchange_cards_input(index, false, name(exchange.lower), &exchange.lower_cards),
+    ]
+}
+
+/// The swaps to tick off before the next round, with somewhere to write down the cards passed.
+pub fn exchange_checklist(game: &Game) -> Node<Msg> {
+    let ready = game.is_exchange_complete();
+    div![
+        class![
+            "flex",
+            "flex-col",
+            "w-full",
+            "items-center",
+            "mt-4",
+            "mx-auto",
+            "max-w-2xl"
+        ],
+        h2![
+            class!["font-bold", "text-xl", "text-indigo-700"],
+            "Card Exchange"
+        ],
+        ul![
+            class!["text-lg", "mx-4"],
+            game.exchanges
+                .iter()
+                .enumerate()
+                .map(|(i, exchange)| exchange_row(i, exchange, &game.players)),
+        ],
+        button![
+            class![
+                "px-6",
+                "py-4",
+                "mt-2",
+                "rounded-full",
+                "font-bold",
+                "text-white",
+                "hover:shadow" => ready,
+                "bg-green-600" => ready,
+                "hover:bg-green-800" => ready,
+                "bg-gray-600" => !ready,
             ],
-            "passes their ",
-            em!["two highest cards "],
-            "to ",
-            strong![tycoon.name.as_str()],
-            ", and receives the first extra card (if applicable)",
+            attrs! {At::Disabled => (!ready).as_at_value()},
+            simple_ev(Ev::Click, Msg::StartRound),
+            "Start Round"
         ],
     ]
 }
@@ -172,33 +783,33 @@ pub fn player_instructions(players: &HashMap<usize, Player>) -> Node<Msg> {
 pub fn setup_mode(setup_state: &SetupState) -> Node<Msg> {
     div![
         class!["flex", "flex-col", "w-full", "max-w-2xl"],
-        h1![class!["font-bold", "text-xl", "text-center"], "Add Players"],
+        h1![class!["font-bold", "text-xl", "text-center"], "Players"],
+        p![
+            class!["text-red-700", "font-bold", "text-center"],
+            match &setup_state.error {
+                Some(s) => s.as_str(),
+                None => "",
+            },
+        ],
         div![
             id!["add-player-inputs"],
-            (0..setup_state.num_of_inputs).map(|i| {
-                input![
-                    class![
-                        "bg-indigo-100",
-                        "border-indigo-500",
-                        "border",
-                        "rounded",
-                        "px-4",
-                        "py-2",
-                        "block",
-                        "my-2",
-                        "w-full",
-                        "max-w-xl",
-                        "mx-auto"
-                    ],
-                    attrs! {
-                        At::Type => "text".to_string(),
-                        At::Placeholder => format! ("Player {}", i + 1),
-                        At::Value => setup_state.player_names.get(&i).unwrap_or(&"".to_string()),
-                    },
-                    input_ev(Ev::Input, move |text| Msg::AddPlayer(text, i)),
-                    keyboard_ev(Ev::KeyDown, Msg::AddPlayerOnEnter),
-                ]
-            }),
+            setup_state
+                .roster
+                .iter()
+                .enumerate()
+                .map(|(i, seat)| roster_row(i, seat, setup_state.roster.len())),
+            if setup_state.roster.iter().any(|seat| seat.pid.is_some()) {
+                keep_scores_input(setup_state.keep_scores)
+            } else {
+                empty![]
+            },
+            game_length_inputs(&setup_state.game_length),
+            scoring_inputs(&setup_state.scoring),
+            bankruptcy_input(setup_state.bankruptcy),
+            revolution_bonus_input(setup_state.revolution_bonus),
+            foul_finish_input(setup_state.foul_finish),
+            deck_inputs(setup_state.deck),
+            tie_break_input(setup_state.tie_break),
             div![
                 class!["flex"],
                 button![
@@ -228,9 +839,25 @@ pub fn setup_mode(setup_state: &SetupState) -> Node<Msg> {
                         "mt-2",
                         "mx-auto"
                     ],
-                    simple_ev(Ev::Click, Msg::MorePlayers),
+                    simple_ev(Ev::Click, Msg::AddPlayer),
                     "Add Another"
                 ],
+                button![
+                    class![
+                        "px-4",
+                        "py-2",
+                        "bg-indigo-600",
+                        "hover:shadow",
+                        "hover:bg-indigo-800",
+                        "text-white",
+                        "rounded-full",
+                        "mt-2",
+                        "mx-auto"
+                    ],
+                    attrs! {At::Title => "Fill the empty seats with computer players"},
+                    simple_ev(Ev::Click, Msg::AddBots),
+                    "Add Bots"
+                ],
                 button![
                     class![
                         "px-4",
@@ -251,7 +878,403 @@ pub fn setup_mode(setup_state: &SetupState) -> Node<Msg> {
     ]
 }
 
-pub fn game_over_mode(ranking: Vec<Player>) -> Node<Msg> {
+fn roster_button(text: &str, enabled: bool, msg: Msg) -> Node<Msg> {
+    button![
+        class![
+            "px-2",
+            "py-1",
+            "ml-2",
+            "rounded-sm",
+            "text-white",
+            "hover:shadow" => enabled,
+            "bg-indigo-600" => enabled,
+            "hover:bg-indigo-800" => enabled,
+            "bg-gray-600" => !enabled,
+        ],
+        attrs! {At::Disabled => (!enabled).as_at_value()},
+        text,
+        simple_ev(Ev::Click, msg),
+    ]
+}
+
+fn roster_row(i: usize, seat: &RosterSeat, num_seats: usize) -> Node<Msg> {
+    div![
+        class![
+            "flex",
+            "items-center",
+            "w-full",
+            "max-w-xl",
+            "mx-auto",
+            "my-2"
+        ],
+        input![
+            class![
+                "bg-indigo-100",
+                "border-indigo-500",
+                "border",
+                "rounded",
+                "px-4",
+                "py-2",
+                "flex-grow"
+            ],
+            attrs! {
+                At::Type => "text".to_string(),
+                At::Placeholder => format!("Player {}", i + 1),
+                At::Value => seat.name,
+            },
+            input_ev(Ev::Input, move |text| Msg::SetPlayerName(i, text)),
+            keyboard_ev(Ev::KeyDown, Msg::AddPlayerOnEnter),
+        ],
+        bot_select(i, seat.bot),
+        roster_button("Up", i > 0, Msg::MovePlayerUp(i)),
+        roster_button("Down", i + 1 < num_seats, Msg::MovePlayerUp(i + 1)),
+        roster_button("Remove", num_seats > 1, Msg::RemovePlayer(i)),
+    ]
+}
+
+fn bot_select(i: usize, bot: Option<Difficulty>) -> Node<Msg> {
+    select![
+        class![
+            "bg-indigo-100",
+            "border-indigo-500",
+            "border",
+            "rounded",
+            "px-2",
+            "py-2",
+            "ml-2"
+        ],
+        option![
+            attrs! {
+                At::Value => "person",
+                At::Selected => bot.is_none().as_at_value(),
+            },
+            "Person"
+        ],
+        Difficulty::all().iter().map(|d| option![
+            attrs! {
+                At::Value => d.key(),
+                At::Selected => (bot == Some(*d)).as_at_value(),
+            },
+            d.label()
+        ]),
+        input_ev(Ev::Change, move |key| Msg::SetSeatBot(i, key)),
+    ]
+}
+
+fn keep_scores_input(keep_scores: bool) -> Node<Msg> {
+    label![
+        class![
+            "flex",
+            "w-full",
+            "max-w-xl",
+            "mx-auto",
+            "my-2",
+            "items-center"
+        ],
+        input![
+            class!["mr-2"],
+            attrs! {
+                At::Type => "checkbox",
+                At::Checked => keep_scores.as_at_value(),
+            },
+            ev(Ev::Change, move |_| Msg::SetKeepScores(!keep_scores)),
+        ],
+        "Keep the scores and carry on with this game",
+    ]
+}
+
+fn number_select(
+    values: std::ops::RangeInclusive<u8>,
+    current: u8,
+    msg: fn(String) -> Msg,
+) -> Node<Msg> {
+    select![
+        class![
+            "bg-indigo-100",
+            "border-indigo-500",
+            "border",
+            "rounded",
+            "px-4",
+            "py-2",
+            "mx-2"
+        ],
+        values.map(|n| option![
+            attrs! {
+                At::Value => n,
+                At::Selected => (n == current).as_at_value(),
+            },
+            n.to_string()
+        ]),
+        input_ev(Ev::Change, msg),
+    ]
+}
+
+fn deck_inputs(deck: Deck) -> Node<Msg> {
+    div![
+        class![
+            "flex",
+            "w-full",
+            "max-w-xl",
+            "mx-auto",
+            "my-2",
+            "items-center"
+        ],
+        label![class!["font-bold", "mr-2"], "Cards"],
+        number_select(1..=Deck::MAX_DECKS, deck.decks, Msg::SetDecks),
+        span!["decks with"],
+        number_select(0..=deck.max_jokers(), deck.jokers, Msg::SetJokers),
+        span![format!("jokers ({} cards)", deck.size())],
+    ]
+}
+
+fn foul_finish_input(foul_finish: bool) -> Node<Msg> {
+    label![
+        class![
+            "flex",
+            "w-full",
+            "max-w-xl",
+            "mx-auto",
+            "my-2",
+            "items-center"
+        ],
+        input![
+            class!["mr-2"],
+            attrs! {
+                At::Type => "checkbox",
+                At::Checked => foul_finish.as_at_value(),
+            },
+            ev(Ev::Change, move |_| Msg::SetFoulFinish(!foul_finish)),
+        ],
+        "Going out on a Joker, a 2, an 8 or the 3 of spades is a foul, and ranks last",
+    ]
+}
+
+fn game_length_inputs(game_length: &GameLength) -> Node<Msg> {
+    div![
+        class![
+            "flex",
+            "w-full",
+            "max-w-xl",
+            "mx-auto",
+            "my-2",
+            "items-center"
+        ],
+        label![class!["font-bold", "mr-2"], "Play for"],
+        input![
+            class![
+                "bg-indigo-100",
+                "border-indigo-500",
+                "border",
+                "rounded",
+                "px-4",
+                "py-2",
+                "w-24"
+            ],
+            attrs! {
+                At::Type => "number".to_string(),
+                At::Min => "1".to_string(),
+                At::Value => game_length.value().to_string(),
+            },
+            input_ev(Ev::Input, Msg::SetGameLengthValue),
+        ],
+        select![
+            class![
+                "bg-indigo-100",
+                "border-indigo-500",
+                "border",
+                "rounded",
+                "px-4",
+                "py-2",
+                "ml-2"
+            ],
+            option![
+                attrs! {
+                    At::Value => "rounds",
+                    At::Selected => (game_length.kind() == "rounds").as_at_value(),
+                },
+                "rounds"
+            ],
+            option![
+                attrs! {
+                    At::Value => "points",
+                    At::Selected => (game_length.kind() == "points").as_at_value(),
+                },
+                "points (first to reach)"
+            ],
+            input_ev(Ev::Change, Msg::SetGameLengthKind),
+        ],
+    ]
+}
+
+/// A labelled drop-down for one of the rule choices, given as (key, label, selected).
+fn rule_select(
+    text: &str,
+    choices: Vec<(&'static str, &'static str, bool)>,
+    msg: fn(String) -> Msg,
+) -> Node<Msg> {
+    div![
+        class![
+            "flex",
+            "w-full",
+            "max-w-xl",
+            "mx-auto",
+            "my-2",
+            "items-center"
+        ],
+        label![class!["font-bold", "mr-2"], text],
+        select![
+            class![
+                "bg-indigo-100",
+                "border-indigo-500",
+                "border",
+                "rounded",
+                "px-4",
+                "py-2"
+            ],
+            choices.into_iter().map(|(key, label, selected)| option![
+                attrs! {
+                    At::Value => key,
+                    At::Selected => selected.as_at_value(),
+                },
+                label
+            ]),
+            input_ev(Ev::Change, msg),
+        ],
+    ]
+}
+
+fn tie_break_input(tie_break: TieBreak) -> Node<Msg> {
+    rule_select(
+        "Break ties by",
+        TieBreak::all()
+            .iter()
+            .map(|t| (t.key(), t.label(), *t == tie_break))
+            .collect(),
+        Msg::SetTieBreak,
+    )
+}
+
+fn bankruptcy_input(bankruptcy: Bankruptcy) -> Node<Msg> {
+    rule_select(
+        "Bankruptcy",
+        Bankruptcy::all()
+            .iter()
+            .map(|b| (b.key(), b.label(), *b == bankruptcy))
+            .collect(),
+        Msg::SetBankruptcy,
+    )
+}
+
+fn revolution_bonus_input(bonus: i32) -> Node<Msg> {
+    div![
+        class![
+            "flex",
+            "w-full",
+            "max-w-xl",
+            "mx-auto",
+            "my-2",
+            "items-center"
+        ],
+        label![class!["font-bold", "mr-2"], "Points per revolution"],
+        input![
+            class![
+                "bg-indigo-100",
+                "border-indigo-500",
+                "border",
+                "rounded",
+                "px-2",
+                "py-1",
+                "w-16"
+            ],
+            attrs! {
+                At::Type => "number".to_string(),
+                At::Value => bonus.to_string(),
+            },
+            input_ev(Ev::Input, Msg::SetRevolutionBonus),
+        ],
+    ]
+}
+
+fn rank_points_input(scoring: &ScoringTable, rank: Rank) -> Node<Msg> {
+    label![
+        class!["flex", "flex-col", "items-center", "mx-1"],
+        span![class!["text-sm"], rank.to_string()],
+        input![
+            class![
+                "bg-indigo-100",
+                "border-indigo-500",
+                "border",
+                "rounded",
+                "px-2",
+                "py-1",
+                "w-16"
+            ],
+            attrs! {
+                At::Type => "number".to_string(),
+                At::Value => scoring.points(rank).to_string(),
+            },
+            input_ev(Ev::Input, move |value| Msg::SetRankPoints(rank, value)),
+        ],
+    ]
+}
+
+fn scoring_inputs(scoring: &ScoringTable) -> Node<Msg> {
+    let current_preset = scoring.preset_name();
+    div![
+        class!["flex", "flex-col", "w-full", "max-w-xl", "mx-auto", "my-2"],
+        div![
+            class!["flex", "items-center"],
+            label![class!["font-bold", "mr-2"], "Scoring"],
+            select![
+                class![
+                    "bg-indigo-100",
+                    "border-indigo-500",
+                    "border",
+                    "rounded",
+                    "px-4",
+                    "py-2"
+                ],
+                ScoringTable::presets().into_iter().map(|(name, _)| {
+                    option![
+                        attrs! {
+                            At::Value => name,
+                            At::Selected => (current_preset == Some(name)).as_at_value(),
+                        },
+                        name
+                    ]
+                }),
+                option![
+                    attrs! {
+                        At::Value => "custom",
+                        At::Selected => current_preset.is_none().as_at_value(),
+                    },
+                    "Custom"
+                ],
+                input_ev(Ev::Change, Msg::ChooseScoringPreset),
+            ],
+        ],
+        div![
+            class!["flex", "justify-between", "mt-2"],
+            vec![
+                Rank::Tycoon,
+                Rank::Rich,
+                Rank::Commoner,
+                Rank::Poor,
+                Rank::Beggar
+            ]
+            .into_iter()
+            .map(|rank| rank_points_input(scoring, rank)),
+        ],
+    ]
+}
+
+pub fn game_over_mode(game: &Game) -> Node<Msg> {
+    let standings = game.standings();
+    let winners: Vec<&str> = standings
+        .iter()
+        .filter(|s| s.place == 1)
+        .map(|s| game.players[s.pid].name.as_str())
+        .collect();
     div![
         class![
             "flex",
@@ -265,9 +1288,13 @@ pub fn game_over_mode(ranking: Vec<Player>) -> Node<Msg> {
         section![
             span![
                 class!["text-indigo-600", "font-bold", "text-2xl"],
-                "WINNER: "
+                if winners.len() == 1 {
+                    "WINNER: "
+                } else {
+                    "WINNERS: "
+                }
             ],
-            span![class!["font-bold", "text-2xl"], ranking[0].name.as_str()],
+            span![class!["font-bold", "text-2xl"], winners.join(" & ")],
         ],
         section![
             h4![
@@ -280,12 +1307,50 @@ pub fn game_over_mode(ranking: Vec<Player>) -> Node<Msg> {
                 ],
                 "Game Summary",
             ],
-            ranking.iter().map(|p| player_summary(p)),
+            standings
+                .iter()
+                .map(|s| player_summary(&game.players[s.pid], s)),
         ]
     ]
 }
 
-fn player_summary(player: &Player) -> Node<Msg> {
+fn download_link(text: &str, filename: &str, href: String) -> Node<Msg> {
+    a![
+        class![
+            "px-4",
+            "py-2",
+            "bg-indigo-600",
+            "hover:shadow",
+            "hover:bg-indigo-800",
+            "text-white",
+            "rounded-full",
+            "mx-2"
+        ],
+        attrs! {
+            At::Href => href,
+            At::Download => filename,
+        },
+        text
+    ]
+}
+
+pub fn export_links(game: &Game) -> Node<Msg> {
+    div![
+        class!["flex", "w-full", "justify-center", "mt-4"],
+        download_link(
+            "Export JSON",
+            "tycoon-game.json",
+            download::data_url("application/json", &download::game_json(game)),
+        ),
+        download_link(
+            "Export CSV",
+            "tycoon-game.csv",
+            download::data_url("text/csv", &download::game_csv(game)),
+        ),
+    ]
+}
+
+fn player_summary(player: &Player, standing: &Standing) -> Node<Msg> {
     div![
         class![
             "my-2",
@@ -309,6 +1374,11 @@ fn player_summary(player: &Player) -> Node<Msg> {
             ],
             player.name.as_str()
         ],
+        div![
+            class!["w-full"],
+            span![class!["text-indigo-600"], "Placing: "],
+            standing.place_text(),
+        ],
         div![
             class!["w-full"],
             span![class!["text-indigo-600"], "Final Rank: "],
@@ -325,11 +1395,12 @@ fn player_summary(player: &Player) -> Node<Msg> {
         div![
             class!["w-full"],
             span![class!["text-indigo-600"], "Past Ranks: "],
-            format!(
-                "{}, {}",
-                player.past_ranks[0].to_string(),
-                player.past_ranks[1].to_string()
-            )
+            player
+                .past_ranks
+                .iter()
+                .map(|r| r.to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
         ]
     ]
 }
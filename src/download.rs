@@ -0,0 +1,24 @@
+use tycoon_core::{Game, GameExport};
+
+/// Turns `contents` into a link target the browser will download as a file, so exporting doesn't
+/// need a server or any extra browser APIs.
+pub fn data_url(mime: &str, contents: &str) -> String {
+    let mut url = format!("data:{};charset=utf-8,", mime);
+    for byte in contents.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                url.push(byte as char)
+            }
+            _ => url.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    url
+}
+
+pub fn game_json(game: &Game) -> String {
+    serde_json::to_string_pretty(&GameExport::new(game)).unwrap_or_default()
+}
+
+pub fn game_csv(game: &Game) -> String {
+    GameExport::new(game).to_csv()
+}
@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+
+/// Only keep this many steps, so a long evening doesn't fill up local storage.
+const MAX_HISTORY: usize = 200;
+
+/// Undo and redo stacks of whole snapshots of the game.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct History<T> {
+    undo: Vec<T>,
+    redo: Vec<T>,
+}
+
+impl<T> Default for History<T> {
+    fn default() -> Self {
+        Self {
+            undo: vec![],
+            redo: vec![],
+        }
+    }
+}
+
+impl<T> History<T> {
+    /// Remember the state from before an action. Anything that was undone can't be redone
+    /// after a new action.
+    pub fn record(&mut self, state: T) {
+        if self.undo.len() == MAX_HISTORY {
+            self.undo.remove(0);
+        }
+        self.undo.push(state);
+        self.redo.clear();
+    }
+
+    pub fn undo(&mut self, current: T) -> Option<T> {
+        let previous = self.undo.pop()?;
+        self.redo.push(current);
+        Some(previous)
+    }
+
+    pub fn redo(&mut self, current: T) -> Option<T> {
+        let next = self.redo.pop()?;
+        self.undo.push(current);
+        Some(next)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+}
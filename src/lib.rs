@@ -1,204 +1,588 @@
 use seed::{prelude::*, *};
-use std::cmp::Ordering;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use tycoon_core::{
+    bots, check_names, Bankruptcy, Difficulty, Game, GameExport, League, PlayerOrder, RatingBasis,
+    RosterSeat, ScoringTable, Shuffler, Table, TieBreak, MAX_PLAYERS,
+};
+use wasm_bindgen_futures::JsFuture;
 
 mod components;
+mod download;
+mod history;
 mod msg;
-mod player;
+mod storage;
 
-use crate::components::{game_over_mode, header, player_instructions, score_table, setup_mode};
+use crate::components::{
+    exchange_checklist, export_links, game_over_mode, header, history_controls, import_button,
+    league_table, player_instructions, practice_table, ratings_table, resume_prompt,
+    revolution_status, round_display, score_history, score_table, setup_mode, sort_controls,
+};
+use crate::history::History;
 use crate::msg::{Msg, SetupState};
-use crate::player::{Player, Rank};
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 enum Mode {
     Normal,
     Setup,
     GameOver,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 struct Model {
-    players: HashMap<usize, Player>,
-    round: u8,
+    game: Game,
     mode: Mode,
+    #[serde(skip)]
     setup_state: SetupState,
-    players_out: HashMap<usize, Rank>,
-    beggar_message: Option<String>,
+    /// A game found in local storage on startup that the user hasn't resumed or discarded yet.
+    #[serde(skip)]
+    saved_game: Option<Box<Model>>,
+    #[serde(default)]
+    history: History<Snapshot>,
+    /// Why the last game file couldn't be imported.
+    #[serde(skip)]
+    import_error: Option<String>,
+    #[serde(default)]
+    order: PlayerOrder,
+    /// The cards, when computer players are playing the round out.
+    #[serde(default)]
+    table: Option<Table>,
+    /// Positions in the hand of the cards picked to play next.
+    #[serde(skip)]
+    selected: Vec<usize>,
+    /// Why the last play wasn't allowed.
+    #[serde(skip)]
+    play_error: Option<String>,
+    /// Every game finished this session.
+    #[serde(default)]
+    league: League,
+    /// Whether this game is the last one in the league, so it isn't added twice.
+    #[serde(default)]
+    archived: bool,
+    #[serde(default)]
+    rating_basis: RatingBasis,
+}
+
+/// Everything an undo needs to put back.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Snapshot {
+    game: Game,
+    mode: Mode,
+    #[serde(default)]
+    table: Option<Table>,
+    /// How many games the league held. The league itself isn't copied into every step, since
+    /// only its last game ever changes.
+    #[serde(default)]
+    league_games: usize,
+    #[serde(default)]
+    archived: bool,
+    /// The whole league, only for the step that cleared it.
+    #[serde(default)]
+    league: Option<League>,
 }
 
 impl Default for Model {
     fn default() -> Self {
         Self {
-            players: HashMap::new(),
-            round: 1,
+            game: Game::default(),
             mode: Mode::Normal,
             setup_state: SetupState::new(),
-            players_out: HashMap::new(),
-            beggar_message: None,
+            saved_game: None,
+            history: History::default(),
+            import_error: None,
+            order: PlayerOrder::default(),
+            table: None,
+            selected: vec![],
+            play_error: None,
+            league: League::default(),
+            archived: false,
+            rating_basis: RatingBasis::default(),
         }
     }
 }
 
 impl Model {
-    pub fn find_tycoon(&self) -> Option<usize> {
-        for (i, player) in self.players.iter() {
-            if player.is_tycoon() {
-                return Some(*i);
-            }
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            game: self.game.clone(),
+            mode: self.mode.clone(),
+            table: self.table.clone(),
+            league_games: self.league.games.len(),
+            archived: self.archived,
+            league: None,
         }
-        None
     }
 
-    pub fn find_last_not_out(&self) -> Option<usize> {
-        for (i, _) in self.players.iter() {
-            if !self.players_out.contains_key(i) {
-                return Some(*i);
+    fn restore(&mut self, snapshot: Snapshot) {
+        if let Some(league) = snapshot.league {
+            self.league = league;
+        } else {
+            // Put back the league's games from before this game, then this game if it had finished
+            let earlier = snapshot
+                .league_games
+                .saturating_sub(usize::from(snapshot.archived));
+            self.league.games.truncate(earlier);
+            if snapshot.archived {
+                self.league.archive(&snapshot.game);
             }
         }
-        None
+        self.archived = snapshot.archived;
+        self.game = snapshot.game;
+        self.table = snapshot.table;
+        self.selected.clear();
+        self.play_error = None;
+        // Undoing saving the players goes back to the old game, not to the setup form
+        self.mode = match snapshot.mode {
+            Mode::Setup => Mode::Normal,
+            mode => mode,
+        };
     }
 
-    // OH THE HUMANITY
-    pub fn handle_go_out(&mut self, pid: usize) {
-        match self.players_out.len() {
-            0 => {
-                // First person to go out is tycoon. period.
-                self.players_out.insert(pid, Rank::Tycoon);
-                // In round one this has no side effects, but otherwise
-                if self.round != 1 {
-                    match self.find_tycoon() {
-                        Some(tid) => {
-                            // If the person who went out is not already the tycoon, the tycoon
-                            // goes bankrupt and is automatically the beggar
-                            if tid != pid {
-                                self.players_out.insert(tid, Rank::Beggar);
-                                let new_tycoon_name = match self.players.get(&pid) {
-                                    Some(x) => x.name.as_str(),
-                                    None => "",
-                                };
-                                let new_beggar_name = match self.players.get(&tid) {
-                                    Some(x) => x.name.as_str(),
-                                    None => "",
-                                };
-                                self.beggar_message = Some(format!(
-                                    "{} went out so {} is the beggar automatically.",
-                                    new_tycoon_name, new_beggar_name
-                                ));
-                            }
-                        }
-                        None => (),
-                    }
-                };
-            }
-            1 => {
-                // The only time when this will occur is round 1 or the first out was already
-                // the tycoon.
-                self.players_out.insert(pid, Rank::Rich);
-            }
-            2 => {
-                // If the tycoon did not go out first, then there will already be a beggar
-                let contains_beggar = self.players_out.values().any(|x| *x == Rank::Beggar);
-                // if there is a beggar, next to go out is rich, and last is poor
-                if contains_beggar {
-                    self.players_out.insert(pid, Rank::Rich);
-                    match self.find_last_not_out() {
-                        Some(lid) => {
-                            self.players_out.insert(lid, Rank::Poor);
-                        }
-                        None => (),
-                    }
-                } else {
-                    // if not, then we have poor then beggar
-                    self.players_out.insert(pid, Rank::Poor);
-                    match self.find_last_not_out() {
-                        Some(lid) => {
-                            self.players_out.insert(lid, Rank::Beggar);
-                        }
-                        None => (),
-                    }
-                };
-            }
-            _ => (),
+    /// Remember the current state so the action about to happen can be undone.
+    fn record(&mut self) {
+        let snapshot = self.snapshot();
+        self.history.record(snapshot);
+    }
+
+    fn undo(&mut self) {
+        let current = self.snapshot();
+        if let Some(previous) = self.history.undo(current) {
+            self.restore(previous);
         }
     }
 
-    pub fn handle_end_round(&mut self) {
-        for (i, player) in self.players.iter_mut() {
-            match self.players_out.get(i) {
-                Some(r) => {
-                    player.set_rank(r.clone());
-                    player.update_score()
-                }
-                None => (),
-            }
+    fn redo(&mut self) {
+        let current = self.snapshot();
+        if let Some(next) = self.history.redo(current) {
+            self.restore(next);
         }
-        self.players_out = HashMap::new();
-        self.round += 1;
-        self.beggar_message = None;
     }
 
-    pub fn get_ranking(&self) -> Vec<Player> {
-        let mut players: Vec<Player> = self.players.iter().map(|(_, p)| p.clone()).collect();
-        players.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(Ordering::Less));
-        players
+    /// Adds the finished game to the league, unless it's already there.
+    fn archive(&mut self) {
+        if !self.archived {
+            self.league.archive(&self.game);
+            self.archived = true;
+        }
     }
 
-    pub fn new_game(&mut self) {
-        for (_, player) in self.players.iter_mut() {
-            player.score = 0;
-            player.rank = None;
-            player.past_ranks = vec![];
+    /// Takes the game back out of the league, when it's been changed after it finished.
+    fn unarchive(&mut self) {
+        if self.archived {
+            self.league.games.pop();
+            self.archived = false;
         }
-        self.round = 1;
-        self.players_out = HashMap::new();
+    }
+
+    pub fn new_game(&mut self) {
+        self.game.new_game();
         self.mode = Mode::Normal;
+        self.archived = false;
+        self.deal_practice();
+    }
+
+    /// Deals a fresh round when computer players are at the table, making the card exchange for
+    /// everyone and letting the bots play up to the first person's turn.
+    fn deal_practice(&mut self) {
+        self.selected.clear();
+        self.play_error = None;
+        if !self.game.has_bots() || self.game.is_game_over() {
+            self.table = None;
+            return;
+        }
+        let mut table = Table::deal(&self.game, &mut Shuffler::new(random_seed()));
+        if self.game.exchanging {
+            bots::exchange_all(&mut table, &mut self.game);
+        }
+        self.table = Some(table);
+        self.play_bots();
+    }
+
+    fn play_bots(&mut self) {
+        if let Some(table) = self.table.as_mut() {
+            bots::play_bots(table, &mut self.game, &mut Shuffler::new(random_seed()));
+        }
+    }
+
+    /// Makes a move for the person whose turn it is, then lets the bots reply.
+    fn take_turn(&mut self, play: bool) {
+        let table = match self.table.as_mut() {
+            Some(table) => table,
+            None => return,
+        };
+        let before = Snapshot {
+            game: self.game.clone(),
+            mode: self.mode.clone(),
+            table: Some(table.clone()),
+            league_games: self.league.games.len(),
+            archived: self.archived,
+            league: None,
+        };
+        let pid = table.turn;
+        let result = if play {
+            let cards: Vec<_> = self
+                .selected
+                .iter()
+                .filter_map(|i| table.hands[pid].get(*i).copied())
+                .collect();
+            table.play(&mut self.game, pid, &cards)
+        } else {
+            table.pass(&self.game, pid)
+        };
+        match result {
+            Ok(()) => {
+                self.history.record(before);
+                self.selected.clear();
+                self.play_error = None;
+                self.play_bots();
+            }
+            Err(error) => self.play_error = Some(format!("You can't do that: {}.", error)),
+        }
     }
+
+    /// Replaces the game with one from an exported file, picking up where it left off, or showing
+    /// the summary if it was already over.
+    fn import(&mut self, text: &str) -> Result<(), String> {
+        let export: GameExport = serde_json::from_str(text)
+            .map_err(|e| format!("This isn't an exported Tycoon game: {}", e))?;
+        let game = export
+            .into_game()
+            .map_err(|e| format!("This game can't be imported: {}.", e))?;
+        self.record();
+        self.game = game;
+        self.archived = false;
+        self.mode = if self.game.is_game_over() {
+            Mode::GameOver
+        } else {
+            Mode::Normal
+        };
+        self.deal_practice();
+        Ok(())
+    }
+}
+
+/// A seed for shuffling, since the browser has no other source of randomness handy.
+fn random_seed() -> u64 {
+    (js_sys::Math::random() * u64::MAX as f64) as u64
 }
 
-fn update(msg: Msg, model: &mut Model, _orders: &mut impl Orders<Msg>) {
+fn after_mount(_: Url, _: &mut impl Orders<Msg>) -> AfterMount<Model> {
+    let saved_game = match storage::load() {
+        Some(saved) if !saved.game.players.is_empty() => Some(Box::new(saved)),
+        _ => None,
+    };
+    AfterMount::new(Model {
+        saved_game,
+        ..Model::default()
+    })
+}
+
+fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
+    handle_msg(msg, model, orders);
+    // Don't clobber a saved game before the user has decided what to do with it
+    if model.saved_game.is_none() {
+        storage::save(model);
+    }
+}
+
+fn handle_msg(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
     match msg {
-        Msg::Setup => model.mode = Mode::Setup,
+        Msg::Setup => {
+            let state = &mut model.setup_state;
+            state.roster = model
+                .game
+                .players
+                .iter()
+                .enumerate()
+                .map(|(pid, player)| RosterSeat {
+                    name: player.name.clone(),
+                    pid: Some(pid),
+                    bot: player.bot,
+                })
+                .collect();
+            if state.roster.is_empty() {
+                state.roster.push(SetupState::empty_seat());
+            }
+            // Editing the players halfway through a game shouldn't throw the scores away
+            state.keep_scores = model.game.round > 1;
+            state.error = None;
+            model.setup_state.game_length = model.game.game_length;
+            model.setup_state.scoring = model.game.scoring;
+            model.setup_state.tie_break = model.game.tie_break;
+            model.setup_state.bankruptcy = model.game.bankruptcy;
+            model.setup_state.revolution_bonus = model.game.revolution_bonus;
+            model.setup_state.foul_finish = model.game.foul_finish;
+            model.setup_state.deck = model.game.deck;
+            model.mode = Mode::Setup;
+        }
         Msg::SetupComplete => model.mode = Mode::Normal,
-        Msg::AddPlayer(name, idx) => {
-            model.setup_state.player_names.insert(idx, name);
+        Msg::SetPlayerName(idx, name) => {
+            if let Some(seat) = model.setup_state.roster.get_mut(idx) {
+                seat.name = name;
+            }
         }
-        Msg::MorePlayers => model.setup_state.num_of_inputs += 1,
+        Msg::AddPlayer => {
+            if model.setup_state.roster.len() < MAX_PLAYERS {
+                model.setup_state.roster.push(SetupState::empty_seat());
+            }
+        }
+        Msg::RemovePlayer(idx) => {
+            if idx < model.setup_state.roster.len() {
+                model.setup_state.roster.remove(idx);
+            }
+        }
+        Msg::MovePlayerUp(idx) => {
+            if idx > 0 && idx < model.setup_state.roster.len() {
+                model.setup_state.roster.swap(idx - 1, idx);
+            }
+        }
+        Msg::SetSeatBot(idx, key) => {
+            if let Some(seat) = model.setup_state.roster.get_mut(idx) {
+                seat.bot = Difficulty::from_key(&key);
+            }
+        }
+        Msg::AddBots => {
+            let roster = &mut model.setup_state.roster;
+            roster.retain(|seat| !seat.name.trim().is_empty());
+            let mut number = 1;
+            while roster.len() < 4 {
+                let name = format!("Bot {}", number);
+                number += 1;
+                if roster.iter().any(|seat| seat.name.trim() == name) {
+                    continue;
+                }
+                roster.push(RosterSeat {
+                    name,
+                    pid: None,
+                    bot: Some(Difficulty::default()),
+                });
+            }
+        }
+        Msg::SetKeepScores(keep) => model.setup_state.keep_scores = keep,
         Msg::SavePlayers => {
-            for (i, name) in model.setup_state.player_names.iter() {
-                model.players.insert(*i, Player::new(name.as_str()));
+            let state = &mut model.setup_state;
+            let names: Vec<String> = state.roster.iter().map(|s| s.name.trim().into()).collect();
+            if let Err(error) = check_names(&names) {
+                state.error = Some(error);
+                return;
+            }
+            model.record();
+            let state = std::mem::take(&mut model.setup_state);
+            if state.keep_scores && !model.game.players.is_empty() {
+                model.game.edit_roster(&state.roster);
+                model.game.game_length = state.game_length;
+                model.game.scoring = state.scoring;
+                model.game.tie_break = state.tie_break;
+                model.game.bankruptcy = state.bankruptcy;
+                model.game.revolution_bonus = state.revolution_bonus;
+                model.game.foul_finish = state.foul_finish;
+                model.game.deck = state.deck;
+                // The league keeps the game as it finished, so it's archived again after the edit,
+                // or once it finishes again if it was extended
+                model.unarchive();
+                model.mode = if model.game.is_game_over() {
+                    model.archive();
+                    Mode::GameOver
+                } else {
+                    Mode::Normal
+                };
+                // A hand that's partly played can't be reseated, so practice rounds are redealt
+                if model.game.has_bots() {
+                    model.game.restart_round();
+                }
+                model.deal_practice();
+            } else {
+                model.game = Game::new(state.game_length, state.scoring);
+                model.game.tie_break = state.tie_break;
+                model.game.bankruptcy = state.bankruptcy;
+                model.game.revolution_bonus = state.revolution_bonus;
+                model.game.foul_finish = state.foul_finish;
+                model.game.deck = state.deck;
+                model.game.set_players(&names);
+                for (player, seat) in model.game.players.iter_mut().zip(state.roster.iter()) {
+                    player.bot = seat.bot;
+                }
+                model.new_game();
             }
-            model.setup_state.player_names = HashMap::new();
-            model.setup_state.num_of_inputs = 1;
-            model.new_game();
         }
         Msg::GoOut(pid) => {
-            model.handle_go_out(pid);
+            if model.table.is_some() || model.game.players_out.contains_key(&pid) {
+                return;
+            }
+            model.record();
+            model.game.handle_go_out(pid);
+        }
+        Msg::Revolution(pid) => {
+            if model.table.is_some() || model.game.players_out.contains_key(&pid) {
+                return;
+            }
+            model.record();
+            model.game.handle_revolution(pid);
+        }
+        Msg::ConfirmExchange(index, done) => {
+            let changes = match model.game.exchanges.get(index) {
+                Some(exchange) => model.game.exchanging && exchange.done != done,
+                None => false,
+            };
+            if !changes {
+                return;
+            }
+            model.record();
+            model.game.confirm_exchange(index, done);
+        }
+        Msg::SetExchangeCards(index, upper, cards) => {
+            model.game.record_exchange_cards(index, upper, &cards);
+        }
+        Msg::StartRound => {
+            if !model.game.exchanging || !model.game.is_exchange_complete() {
+                return;
+            }
+            model.record();
+            model.game.start_round();
+            model.play_bots();
+        }
+        Msg::FoulFinish(pid) => {
+            if !model.game.foul_finish
+                || model.table.is_some()
+                || model.game.players_out.contains_key(&pid)
+            {
+                return;
+            }
+            model.record();
+            model.game.handle_foul_finish(pid);
         }
         Msg::EndRound => {
-            if model.players_out.len() < model.players.len() {
+            if !model.game.is_round_complete() {
                 return;
             }
-            if model.round == 3 {
+            model.record();
+            model.game.handle_end_round();
+            if model.game.is_game_over() {
                 model.mode = Mode::GameOver;
+                model.archive();
+            }
+            model.deal_practice();
+        }
+        Msg::ClearLeague => {
+            let mut snapshot = model.snapshot();
+            snapshot.league = Some(std::mem::take(&mut model.league));
+            model.history.record(snapshot);
+            model.archived = false;
+        }
+        Msg::SetRatingBasis(key) => {
+            if let Some(basis) = RatingBasis::from_key(&key) {
+                model.rating_basis = basis;
             }
-            model.handle_end_round();
         }
         Msg::NewGame => {
+            model.record();
             model.new_game();
         }
+        Msg::Undo => model.undo(),
+        Msg::Redo => model.redo(),
+        Msg::UndoShortcut(e) => {
+            // Leave the browser's own undo alone while typing in the setup form
+            if let Mode::Setup = model.mode {
+                return;
+            }
+            if !(e.ctrl_key() || e.meta_key()) || e.key().to_lowercase() != "z" {
+                return;
+            }
+            e.prevent_default();
+            if e.shift_key() {
+                model.redo();
+            } else {
+                model.undo();
+            }
+        }
+        Msg::SetGameLengthKind(kind) => {
+            model.setup_state.game_length = model.setup_state.game_length.with_kind(&kind);
+        }
+        Msg::SetGameLengthValue(value) => {
+            model.setup_state.game_length = model.setup_state.game_length.with_value(&value);
+        }
+        Msg::ChooseScoringPreset(name) => {
+            if let Some(scoring) = ScoringTable::preset(&name) {
+                model.setup_state.scoring = scoring;
+            }
+        }
+        Msg::SetRankPoints(rank, value) => {
+            model.setup_state.scoring = model.setup_state.scoring.with_points(rank, &value);
+        }
         Msg::AddPlayerOnEnter(e) => {
-            if e.key() == "Enter" {
-                model.setup_state.num_of_inputs += 1;
+            if e.key() == "Enter" && model.setup_state.roster.len() < MAX_PLAYERS {
+                model.setup_state.roster.push(SetupState::empty_seat());
+            }
+        }
+        Msg::ResumeGame => {
+            if let Some(saved) = model.saved_game.take() {
+                *model = *saved;
+                // Setup inputs aren't saved, so there's nothing to go back to
+                if let Mode::Setup = model.mode {
+                    model.mode = Mode::Normal;
+                }
+            }
+        }
+        Msg::DiscardSavedGame => {
+            model.saved_game = None;
+            storage::clear();
+        }
+        Msg::ImportFile(file) => {
+            orders.perform_cmd(async move {
+                let text = JsFuture::from(file.text()).await.ok();
+                Msg::GameFileLoaded(text.and_then(|t| t.as_string()))
+            });
+        }
+        Msg::GameFileLoaded(text) => {
+            let result = match text {
+                Some(text) => model.import(&text),
+                None => Err("The file couldn't be read.".into()),
+            };
+            model.import_error = result.err();
+        }
+        Msg::SortPlayers(order) => model.order = order,
+        Msg::SetTieBreak(key) => {
+            if let Some(tie_break) = TieBreak::from_key(&key) {
+                model.setup_state.tie_break = tie_break;
+            }
+        }
+        Msg::SetBankruptcy(key) => {
+            if let Some(bankruptcy) = Bankruptcy::from_key(&key) {
+                model.setup_state.bankruptcy = bankruptcy;
+            }
+        }
+        Msg::SetFoulFinish(foul_finish) => model.setup_state.foul_finish = foul_finish,
+        Msg::SetDecks(value) => model.setup_state.deck = model.setup_state.deck.with_decks(&value),
+        Msg::SetJokers(value) => {
+            model.setup_state.deck = model.setup_state.deck.with_jokers(&value)
+        }
+        Msg::SetRevolutionBonus(value) => {
+            if let Ok(bonus) = value.trim().parse() {
+                model.setup_state.revolution_bonus = bonus;
             }
         }
+        Msg::SelectCard(index) => match model.selected.iter().position(|i| *i == index) {
+            Some(i) => {
+                model.selected.remove(i);
+            }
+            None => model.selected.push(index),
+        },
+        Msg::PlayCards => model.take_turn(true),
+        Msg::PassTurn => model.take_turn(false),
     }
 }
 
+fn window_events(_: &Model) -> Vec<EventHandler<Msg>> {
+    vec![keyboard_ev(Ev::KeyDown, Msg::UndoShortcut)]
+}
+
 fn view(model: &Model) -> impl IntoNodes<Msg> {
-    let can_end_round = model.players_out.len() == model.players.len();
+    let can_end_round = model.game.is_round_complete();
     div![
         header(),
+        match &model.saved_game {
+            Some(saved) => resume_prompt(saved.game.round, saved.game.players.len()),
+            None => empty![],
+        },
         match model.mode {
             Mode::Normal => {
                 div![
@@ -241,12 +625,27 @@ fn view(model: &Model) -> impl IntoNodes<Msg> {
                             simple_ev(Ev::Click, Msg::NewGame),
                             "New Game"
                         ],
+                        import_button(),
                     ],
+                    model.import_error.as_ref().map(|error| p![
+                        class!["text-red-700", "font-bold", "text-center", "mt-2"],
+                        error
+                    ]),
+                    history_controls(model.history.can_undo(), model.history.can_redo()),
+                    round_display(model.game.round, &model.game.game_length),
+                    sort_controls(model.order),
                     div![
                         class!["flex", "w-full", "justify-center", "mt-2"],
-                        score_table(&model.players, &model.players_out),
+                        score_table(&model.game, model.order),
                     ],
-                    if model.players.len() == 0 {
+                    revolution_status(&model.game),
+                    model.table.as_ref().map(|table| practice_table(
+                        &model.game,
+                        table,
+                        &model.selected,
+                        &model.play_error
+                    )),
+                    if model.game.players.is_empty() {
                         p![
                             class![
                                 "flex",
@@ -264,7 +663,7 @@ fn view(model: &Model) -> impl IntoNodes<Msg> {
                     },
                     p![
                         class!["text-red-700", "font-bold", "mx-auto", "max-w-3xl"],
-                        match &model.beggar_message {
+                        match &model.game.beggar_message {
                             Some(s) => s.as_str(),
                             None => "",
                         },
@@ -295,22 +694,29 @@ fn view(model: &Model) -> impl IntoNodes<Msg> {
                             "End Round"
                         ],
                     ],
-                    match model.round {
-                        1 => div![],
-                        _ => {
-                            div![
-                                class![
-                                    "flex",
-                                    "w-full",
-                                    "justify-center",
-                                    "mt-2",
-                                    "mx-auto",
-                                    "max-w-2xl"
-                                ],
-                                player_instructions(&model.players),
-                            ]
-                        }
+                    if model.game.players.is_empty() {
+                        div![]
+                    } else {
+                        div![
+                            class![
+                                "flex",
+                                "w-full",
+                                "justify-center",
+                                "mt-2",
+                                "mx-auto",
+                                "max-w-2xl"
+                            ],
+                            player_instructions(&model.game),
+                        ]
+                    },
+                    if model.game.exchanging {
+                        exchange_checklist(&model.game)
+                    } else {
+                        empty![]
                     },
+                    score_history(&model.game, model.order),
+                    league_table(&model.league),
+                    ratings_table(&model.league, model.rating_basis),
                 ]
             }
             Mode::GameOver => {
@@ -343,7 +749,12 @@ fn view(model: &Model) -> impl IntoNodes<Msg> {
                             "New Game"
                         ],
                     ],
-                    game_over_mode(model.get_ranking()),
+                    history_controls(model.history.can_undo(), model.history.can_redo()),
+                    game_over_mode(&model.game),
+                    score_history(&model.game, model.order),
+                    export_links(&model.game),
+                    league_table(&model.league),
+                    ratings_table(&model.league, model.rating_basis),
                 ]
             }
             Mode::Setup => {
@@ -358,5 +769,8 @@ fn view(model: &Model) -> impl IntoNodes<Msg> {
 
 #[wasm_bindgen(start)]
 pub fn render() {
-    App::builder(update, view).build_and_start();
+    App::builder(update, view)
+        .after_mount(after_mount)
+        .window_events(window_events)
+        .build_and_start();
 }
@@ -1,31 +1,109 @@
-use std::collections::HashMap;
 use web_sys;
 
+use tycoon_core::{
+    Bankruptcy, Deck, GameLength, PlayerOrder, Rank, RosterSeat, ScoringTable, TieBreak,
+};
+
 #[derive(Clone)]
 pub enum Msg {
     GoOut(usize),
+    Revolution(usize),
+    /// Going out on an illegal card, under the foul finish rule.
+    FoulFinish(usize),
+    /// Ticks an exchange on the checklist off, or back on.
+    ConfirmExchange(usize, bool),
+    /// The cards passed in an exchange, by the higher ranked player if the flag is set.
+    SetExchangeCards(usize, bool, String),
+    StartRound,
     EndRound,
     NewGame,
+    /// Forgets the games finished this session.
+    ClearLeague,
+    SetRatingBasis(String),
     Setup,
     SetupComplete,
-    AddPlayer(String, usize),
-    MorePlayers,
+    SetPlayerName(usize, String),
+    AddPlayer,
+    RemovePlayer(usize),
+    /// Swaps a player with the one seated before them.
+    MovePlayerUp(usize),
+    /// Hands a seat to the computer at the given difficulty, or back to a person.
+    SetSeatBot(usize, String),
+    /// Fills the table up to four with computer players.
+    AddBots,
+    SetKeepScores(bool),
     SavePlayers,
-    AddPlayerOnEnter(web_sys::KeyboardEvent)
+    SetGameLengthKind(String),
+    SetGameLengthValue(String),
+    ChooseScoringPreset(String),
+    SetRankPoints(Rank, String),
+    AddPlayerOnEnter(web_sys::KeyboardEvent),
+    ResumeGame,
+    Undo,
+    Redo,
+    UndoShortcut(web_sys::KeyboardEvent),
+    DiscardSavedGame,
+    ImportFile(web_sys::File),
+    /// The contents of the chosen file, or `None` if it couldn't be read.
+    GameFileLoaded(Option<String>),
+    SortPlayers(PlayerOrder),
+    SetTieBreak(String),
+    SetBankruptcy(String),
+    SetRevolutionBonus(String),
+    SetFoulFinish(bool),
+    SetDecks(String),
+    SetJokers(String),
+    /// Picks a card in the hand by position, or puts it back.
+    SelectCard(usize),
+    PlayCards,
+    PassTurn,
 }
 
 
 #[derive(Debug)]
 pub struct SetupState {
-    pub num_of_inputs: usize,
-    pub player_names: HashMap<usize, String>,
+    /// The players being edited, in seating order.
+    pub roster: Vec<RosterSeat>,
+    /// Whether saving carries on the current game with the edited players, rather than starting
+    /// a new one.
+    pub keep_scores: bool,
+    pub error: Option<String>,
+    pub game_length: GameLength,
+    pub scoring: ScoringTable,
+    pub tie_break: TieBreak,
+    pub bankruptcy: Bankruptcy,
+    pub revolution_bonus: i32,
+    pub foul_finish: bool,
+    pub deck: Deck,
+}
+
+impl Default for SetupState {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl SetupState {
     pub fn new() -> Self {
         Self {
-            num_of_inputs: 1,
-            player_names: HashMap::new(),
+            roster: vec![SetupState::empty_seat()],
+            keep_scores: false,
+            error: None,
+            game_length: GameLength::default(),
+            scoring: ScoringTable::default(),
+            tie_break: TieBreak::default(),
+            bankruptcy: Bankruptcy::default(),
+            revolution_bonus: 0,
+            foul_finish: false,
+            deck: Deck::default(),
+        }
+    }
+
+    pub fn empty_seat() -> RosterSeat {
+        RosterSeat {
+            name: String::new(),
+            pid: None,
+            bot: None,
         }
     }
 }
@@ -0,0 +1,90 @@
+use seed::{prelude::*, *};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::Model;
+
+const STORAGE_KEY: &str = "tycoon-scorer-game";
+
+/// Bump this whenever the saved model changes shape, and teach `load` how to read the older
+/// versions so nobody loses a game in progress.
+const SAVE_VERSION: u64 = 3;
+
+#[derive(Serialize)]
+struct SaveFile<'a> {
+    version: u64,
+    model: &'a Model,
+}
+
+pub fn save(model: &Model) {
+    let save_file = SaveFile {
+        version: SAVE_VERSION,
+        model,
+    };
+    if let Err(e) = LocalStorage::insert(STORAGE_KEY, &save_file) {
+        error!("Could not save the game", e);
+    }
+}
+
+pub fn load() -> Option<Model> {
+    let save_file: Value = LocalStorage::get(STORAGE_KEY).ok()?;
+    let model = save_file.get("model")?.clone();
+    match save_file.get("version")?.as_u64()? {
+        1 => serde_json::from_value(upgrade_v2(upgrade_v1(model)?)?).ok(),
+        2 => serde_json::from_value(upgrade_v2(model)?).ok(),
+        3 => serde_json::from_value(model).ok(),
+        _ => None,
+    }
+}
+
+/// Version 1 kept the game's fields directly on the model. They now live in `game`.
+fn upgrade_v1(mut model: Value) -> Option<Value> {
+    let fields = model.as_object_mut()?;
+    let mut game = Map::new();
+    for key in [
+        "players",
+        "round",
+        "game_length",
+        "scoring",
+        "players_out",
+        "beggar_message",
+    ]
+    .iter()
+    {
+        if let Some(value) = fields.remove(*key) {
+            game.insert(key.to_string(), value);
+        }
+    }
+    fields.insert("game".into(), Value::Object(game));
+    // The undo history was saved in the old shape too, and isn't worth converting
+    fields.remove("history");
+    Some(model)
+}
+
+/// Version 2 kept players in a map keyed by id. They're now a list in seating order, where the id
+/// is the position.
+fn upgrade_v2(mut model: Value) -> Option<Value> {
+    let game = model.get_mut("game")?.as_object_mut()?;
+    let mut players: Vec<(usize, Value)> = game
+        .remove("players")?
+        .as_object()?
+        .iter()
+        .map(|(id, player)| Some((id.parse().ok()?, player.clone())))
+        .collect::<Option<_>>()?;
+    players.sort_by_key(|(id, _)| *id);
+    // Ids were handed out in order at setup, so they're already the seats
+    if players.iter().enumerate().any(|(seat, (id, _))| seat != *id) {
+        return None;
+    }
+    let players = players.into_iter().map(|(_, player)| player).collect();
+    game.insert("players".into(), Value::Array(players));
+    // The undo history holds old-style games as well
+    model.as_object_mut()?.remove("history");
+    Some(model)
+}
+
+pub fn clear() {
+    if let Err(e) = LocalStorage::remove(STORAGE_KEY) {
+        error!("Could not clear the saved game", e);
+    }
+}